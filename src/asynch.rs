@@ -0,0 +1,820 @@
+//! Async radio support using `embedded-hal-async`
+//!
+//! This mirrors `base::Hal` over async SPI, awaiting busy and DIO pin
+//! transitions rather than polling, and provides async transmit and receive
+//! operations via `Sx128xAsync`. Command encoding is shared with the blocking
+//! driver through `device::transactions`.
+//!
+//! Configuration matches `Sx128x::new` / `Sx128x::configure`, with the
+//! following intentional differences:
+//!
+//! - Busy waits await the busy pin, so `Config::busy_timeout_ms` and
+//!   `Config::command_retries` do not apply (use a timeout on the future)
+//! - `Config::check_command_status` is not supported
+//! - `Config::ranging_fs` is not supported, the radio always idles in standby
+
+use core::fmt::Debug;
+
+#[cfg(not(feature = "defmt"))]
+use log::{debug, trace};
+
+#[cfg(feature = "defmt")]
+use defmt::{debug, trace};
+
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::{
+    delay::DelayNs,
+    digital::Wait,
+    spi::{ErrorType, Operation, SpiDevice},
+};
+
+use crate::{device::*, Error, FREQ_MAX, FREQ_MIN};
+
+/// Error type for async HAL implementations
+pub type AsyncError<Hal> = Error<<Hal as AsyncHal>::CommsError, <Hal as AsyncHal>::PinError>;
+
+/// Async Hal implementation, mirroring `base::Hal`
+#[allow(async_fn_in_trait)]
+pub trait AsyncHal {
+    type CommsError: Debug + 'static;
+    type PinError: Debug + 'static;
+
+    /// Reset the device
+    async fn reset(&mut self) -> Result<(), AsyncError<Self>>;
+
+    /// Wait for the radio device busy pin to de-assert
+    async fn wait_busy(&mut self) -> Result<(), AsyncError<Self>>;
+
+    /// Wait for the radio device ready / irq (DIO) pin to assert
+    async fn wait_dio(&mut self) -> Result<(), AsyncError<Self>>;
+
+    /// Write data with prefix
+    async fn prefix_write(&mut self, prefix: &[u8], data: &[u8]) -> Result<(), AsyncError<Self>>;
+
+    /// Read data with prefix
+    async fn prefix_read(&mut self, prefix: &[u8], data: &mut [u8])
+        -> Result<(), AsyncError<Self>>;
+
+    /// Write the specified command and data
+    async fn write_cmd(&mut self, command: u8, data: &[u8]) -> Result<(), AsyncError<Self>> {
+        trace!("write_cmd cmd: {:02x?} data: {:02x?}", command, data);
+
+        self.wait_busy().await?;
+        let r = self.prefix_write(&[command], data).await;
+        self.wait_busy().await?;
+        r
+    }
+
+    /// Read the specified command and data
+    async fn read_cmd(&mut self, command: u8, data: &mut [u8]) -> Result<(), AsyncError<Self>> {
+        self.wait_busy().await?;
+        let r = self.prefix_read(&[command, 0x00], data).await;
+        self.wait_busy().await?;
+
+        trace!("read_cmd cmd: {:02x?} data: {:02x?}", command, data);
+
+        r
+    }
+
+    /// Write to the specified register
+    async fn write_regs(&mut self, reg: u16, data: &[u8]) -> Result<(), AsyncError<Self>> {
+        let prefix = [Commands::WiteRegister as u8, (reg >> 8) as u8, reg as u8];
+
+        self.wait_busy().await?;
+        let r = self.prefix_write(&prefix, data).await;
+        self.wait_busy().await?;
+        r
+    }
+
+    /// Read from the specified register
+    async fn read_regs(&mut self, reg: u16, data: &mut [u8]) -> Result<(), AsyncError<Self>> {
+        let prefix = [Commands::ReadRegister as u8, (reg >> 8) as u8, reg as u8, 0];
+
+        self.wait_busy().await?;
+        let r = self.prefix_read(&prefix, data).await;
+        self.wait_busy().await?;
+        r
+    }
+
+    /// Write to the specified buffer
+    async fn write_buff(&mut self, offset: u8, data: &[u8]) -> Result<(), AsyncError<Self>> {
+        self.wait_busy().await?;
+        let r = self
+            .prefix_write(&[Commands::WriteBuffer as u8, offset], data)
+            .await;
+        self.wait_busy().await?;
+        r
+    }
+
+    /// Read from the specified buffer
+    async fn read_buff(&mut self, offset: u8, data: &mut [u8]) -> Result<(), AsyncError<Self>> {
+        self.wait_busy().await?;
+        let r = self
+            .prefix_read(&[Commands::ReadBuffer as u8, offset, 0], data)
+            .await;
+        self.wait_busy().await?;
+        r
+    }
+}
+
+/// Async base interface for radio device
+pub struct AsyncBase<Spi, Busy, Ready, Sdn, Delay> {
+    pub spi: Spi,
+    pub busy: Busy,
+    pub ready: Ready,
+    pub sdn: Sdn,
+    pub delay: Delay,
+    /// Device reset timing
+    reset_timing: ResetTiming,
+}
+
+impl<Spi, Busy, Ready, Sdn, Delay> AsyncBase<Spi, Busy, Ready, Sdn, Delay> {
+    /// Create a new async base interface with the default reset timing
+    pub fn new(spi: Spi, busy: Busy, ready: Ready, sdn: Sdn, delay: Delay) -> Self {
+        Self {
+            spi,
+            busy,
+            ready,
+            sdn,
+            delay,
+            reset_timing: ResetTiming::default(),
+        }
+    }
+
+    /// Fetch the device reset timing
+    pub fn reset_timing(&self) -> ResetTiming {
+        self.reset_timing
+    }
+
+    /// Set the device reset timing
+    pub fn set_reset_timing(&mut self, timing: ResetTiming) {
+        self.reset_timing = timing;
+    }
+}
+
+impl<Spi, Busy, Ready, Sdn, PinError, Delay> AsyncHal for AsyncBase<Spi, Busy, Ready, Sdn, Delay>
+where
+    Spi: SpiDevice<u8>,
+    <Spi as ErrorType>::Error: Debug + 'static,
+
+    Busy: Wait<Error = PinError>,
+    Ready: Wait<Error = PinError>,
+    Sdn: OutputPin<Error = PinError>,
+    PinError: Debug + 'static,
+
+    Delay: DelayNs,
+{
+    type CommsError = <Spi as ErrorType>::Error;
+    type PinError = PinError;
+
+    /// Reset the radio
+    async fn reset(&mut self) -> Result<(), AsyncError<Self>> {
+        let t = self.reset_timing;
+
+        self.delay.delay_ms(t.pre_ms).await;
+
+        self.sdn.set_low().map_err(Error::Pin)?;
+
+        self.delay.delay_ms(t.low_ms).await;
+
+        self.sdn.set_high().map_err(Error::Pin)?;
+
+        self.delay.delay_ms(t.post_ms).await;
+
+        Ok(())
+    }
+
+    async fn wait_busy(&mut self) -> Result<(), AsyncError<Self>> {
+        self.busy.wait_for_low().await.map_err(Error::Pin)
+    }
+
+    /// DIO remains asserted until IRQs are cleared, so this awaits the level
+    /// rather than an edge to avoid missing IRQs raised prior to the call
+    async fn wait_dio(&mut self) -> Result<(), AsyncError<Self>> {
+        self.ready.wait_for_high().await.map_err(Error::Pin)
+    }
+
+    async fn prefix_write(&mut self, prefix: &[u8], data: &[u8]) -> Result<(), AsyncError<Self>> {
+        self.spi
+            .transaction(&mut [Operation::Write(prefix), Operation::Write(data)])
+            .await
+            .map_err(Error::Comms)
+    }
+
+    async fn prefix_read(
+        &mut self,
+        prefix: &[u8],
+        data: &mut [u8],
+    ) -> Result<(), AsyncError<Self>> {
+        self.spi
+            .transaction(&mut [Operation::Write(prefix), Operation::Read(data)])
+            .await
+            .map_err(Error::Comms)
+    }
+}
+
+/// Async Sx128x device object
+pub struct Sx128xAsync<Hal> {
+    config: Config,
+    packet_type: PacketType,
+    pub(crate) hal: Hal,
+}
+
+impl<Spi, Busy, Ready, Sdn, PinError, Delay> Sx128xAsync<AsyncBase<Spi, Busy, Ready, Sdn, Delay>>
+where
+    Spi: SpiDevice<u8>,
+    <Spi as ErrorType>::Error: Debug + 'static,
+
+    Busy: Wait<Error = PinError>,
+    Ready: Wait<Error = PinError>,
+    Sdn: OutputPin<Error = PinError>,
+    PinError: Debug + 'static,
+
+    Delay: DelayNs,
+{
+    /// Create an Sx128xAsync with async SPI, busy, ready and reset pins
+    pub async fn spi(
+        spi: Spi,
+        busy: Busy,
+        ready: Ready,
+        sdn: Sdn,
+        delay: Delay,
+        config: &Config,
+    ) -> Result<Self, Error<<Spi as ErrorType>::Error, PinError>> {
+        let mut hal = AsyncBase::new(spi, busy, ready, sdn, delay);
+        hal.set_reset_timing(config.reset_timing);
+
+        Self::new(hal, config).await
+    }
+}
+
+impl<Hal> Sx128xAsync<Hal>
+where
+    Hal: AsyncHal,
+{
+    /// Create a new Sx128xAsync instance over an async Hal implementation
+    pub async fn new(hal: Hal, config: &Config) -> Result<Self, AsyncError<Hal>> {
+        let mut sx128x = Self::build(hal);
+
+        debug!("Resetting device");
+        sx128x.hal.reset().await?;
+
+        // Check communication with the radio
+        let mut d = [0u8; 2];
+        sx128x
+            .hal
+            .read_regs(Registers::LrFirmwareVersionMsb as u16, &mut d)
+            .await?;
+        let firmware_version = crate::decode_firmware_version(d)?;
+        crate::check_firmware_version(config, firmware_version)?;
+
+        debug!("Configuring device");
+        sx128x.configure(config).await?;
+
+        // Calibrate following regulator mode configuration (configure leaves
+        // the device in StandbyRc)
+        if config.calibrate_on_init {
+            debug!("Calibrating device");
+            sx128x
+                .hal
+                .write_cmd(
+                    Commands::Calibrate as u8,
+                    &[CalibrationParams::all().bits()],
+                )
+                .await?;
+        }
+
+        // Ensure state is idle (unless disabled)
+        if !config.skip_standby {
+            sx128x.set_standby().await?;
+        }
+
+        Ok(sx128x)
+    }
+
+    pub(crate) fn build(hal: Hal) -> Self {
+        Self {
+            config: Config::default(),
+            packet_type: PacketType::None,
+            hal,
+        }
+    }
+
+    /// Apply device configuration, as with `Sx128x::configure`
+    pub async fn configure(&mut self, config: &Config) -> Result<(), AsyncError<Hal>> {
+        let (low, high) = config.channel.edges_hz();
+        if low < FREQ_MIN || high > FREQ_MAX {
+            return Err(Error::InvalidFrequency);
+        }
+
+        if config.xtal_freq == 0 {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        // Check configs match
+        if PacketType::from(&config.modem) != PacketType::from(&config.channel) {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        self.set_standby().await?;
+
+        self.hal
+            .write_cmd(
+                Commands::SetRegulatorMode as u8,
+                &[config.regulator_mode as u8],
+            )
+            .await?;
+
+        // Update LNA sensitivity mode
+        let mut lna = [0u8; 1];
+        self.hal
+            .read_regs(Registers::LnaRegime as u16, &mut lna)
+            .await?;
+        let boost = if config.lna_boost { MASK_LNA_REGIME } else { 0 };
+        self.hal
+            .write_regs(
+                Registers::LnaRegime as u16,
+                &[(lna[0] & !MASK_LNA_REGIME) | boost],
+            )
+            .await?;
+
+        // Update modem and channel configuration
+        self.config = config.clone();
+
+        for t in transactions::set_channel(config, self.packet_type, &config.channel).iter() {
+            self.write_transaction(t).await?;
+        }
+        self.packet_type = PacketType::from(&config.channel);
+
+        self.configure_modem(&config.modem).await?;
+
+        self.hal
+            .write_cmd(
+                Commands::SetLongPreamble as u8,
+                &[config.long_preamble as u8],
+            )
+            .await?;
+        self.hal
+            .write_cmd(Commands::SetAutoFs as u8, &[config.auto_fs as u8])
+            .await?;
+
+        // Update power amplifier configuration, limited to the PA range
+        if !(TX_POWER_MIN..=TX_POWER_MAX).contains(&config.pa_config.power)
+            && config.power_out_of_range == OutOfRangePolicy::Error
+        {
+            return Err(Error::InvalidPower(config.pa_config.power));
+        }
+        let power = config.pa_config.power.clamp(TX_POWER_MIN, TX_POWER_MAX);
+        self.config.pa_config.power = power;
+        self.hal
+            .write_cmd(
+                Commands::SetTxParams as u8,
+                &[(power + 18) as u8, config.pa_config.ramp_time as u8],
+            )
+            .await
+    }
+
+    /// Start transmitting a packet
+    pub async fn start_transmit(&mut self, data: &[u8]) -> Result<(), AsyncError<Hal>> {
+        debug!("TX start");
+
+        if data.len() > self.config.modem.max_payload_len() {
+            return Err(Error::InvalidLength);
+        }
+
+        self.set_standby().await?;
+
+        let mut modem = self.config.modem.clone();
+        modem.set_payload_len(data.len() as u8);
+        self.configure_modem(&modem).await?;
+
+        let base = [self.config.tx_base_addr, self.config.rx_base_addr];
+        self.hal
+            .write_cmd(Commands::SetBufferBaseAddress as u8, &base)
+            .await?;
+        self.hal.write_buff(self.config.tx_base_addr, data).await?;
+
+        self.set_irq_dio_mask(Irq::TX_DEFAULT).await?;
+
+        let timeout = self.config.rf_timeout.clone();
+        self.set_timeout_cmd(Commands::SetTx, &timeout).await
+    }
+
+    /// Check for transmit completion
+    pub async fn check_transmit(&mut self) -> Result<bool, AsyncError<Hal>> {
+        let irq = self.get_interrupts().await?;
+
+        trace!("TX poll (irq: {:?})", irq);
+
+        if irq.contains(Irq::TX_DONE) {
+            debug!("TX complete");
+            Ok(true)
+        } else if irq.contains(Irq::RX_TX_TIMEOUT) {
+            debug!("TX timeout");
+            Err(Error::Timeout)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Transmit a packet, awaiting the DIO interrupt for completion
+    pub async fn transmit(&mut self, data: &[u8]) -> Result<(), AsyncError<Hal>> {
+        self.start_transmit(data).await?;
+
+        loop {
+            self.hal.wait_dio().await?;
+
+            if self.check_transmit().await? {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Start receiving using the configured `Config::rf_timeout`
+    pub async fn start_receive(&mut self) -> Result<(), AsyncError<Hal>> {
+        debug!("RX start");
+
+        self.set_standby().await?;
+
+        let base = [self.config.tx_base_addr, self.config.rx_base_addr];
+        self.hal
+            .write_cmd(Commands::SetBufferBaseAddress as u8, &base)
+            .await?;
+
+        let modem = self.config.modem.clone();
+        self.configure_modem(&modem).await?;
+
+        self.set_irq_dio_mask(Irq::RX_DEFAULT).await?;
+
+        let timeout = self.config.rf_timeout.clone();
+        self.set_timeout_cmd(Commands::SetRx, &timeout).await
+    }
+
+    /// Check for a received packet
+    pub async fn check_receive(&mut self) -> Result<bool, AsyncError<Hal>> {
+        let irq = self.get_interrupts().await?;
+
+        trace!("RX poll (irq: {:?})", irq);
+
+        if irq.contains(Irq::CRC_ERROR) {
+            debug!("RX CRC error");
+            Err(Error::InvalidCrc)
+        } else if irq.contains(Irq::RX_TX_TIMEOUT) {
+            debug!("RX timeout");
+            Err(Error::Timeout)
+        } else if irq.contains(Irq::SYNCWORD_ERROR) {
+            debug!("Invalid syncword");
+            Err(Error::InvalidSync)
+        } else if irq.contains(Irq::RX_DONE) {
+            debug!("RX complete");
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Fetch a received packet
+    pub async fn get_received(
+        &mut self,
+        data: &mut [u8],
+    ) -> Result<(usize, PacketInfo), AsyncError<Hal>> {
+        let mut status = [0u8; 2];
+        self.hal
+            .read_cmd(Commands::GetRxBufferStatus as u8, &mut status)
+            .await?;
+
+        let len = match &self.config.modem {
+            #[cfg(feature = "modem-lora")]
+            Modem::LoRa(c) if c.header_type == lora::LoRaHeader::Implicit => {
+                let mut len = [0u8; 1];
+                self.hal
+                    .read_regs(Registers::LrPayloadLength as u16, &mut len)
+                    .await?;
+                len[0]
+            }
+            // BLE status[0] does not include 2-byte PDU header
+            #[cfg(feature = "modem-ble")]
+            Modem::Ble(_) => status[0].saturating_add(2),
+            _ => status[0],
+        } as usize;
+
+        if data.len() < len {
+            return Err(Error::InvalidLength);
+        }
+
+        self.hal.read_buff(status[1], &mut data[..len]).await?;
+
+        let mut raw = [0u8; 5];
+        self.hal
+            .read_cmd(Commands::GetPacketStatus as u8, &mut raw)
+            .await?;
+
+        let mut info = PacketInfo::default();
+        info.decode(self.packet_type, &raw);
+
+        // Check the packet status byte as errors may not result in an IRQ
+        crate::check_packet_status(self.packet_type, &self.config, &mut info)?;
+
+        Ok((len, info))
+    }
+
+    /// Receive a packet, awaiting the DIO interrupt for reception
+    pub async fn receive(
+        &mut self,
+        data: &mut [u8],
+    ) -> Result<(usize, PacketInfo), AsyncError<Hal>> {
+        self.start_receive().await?;
+
+        loop {
+            self.hal.wait_dio().await?;
+
+            if self.check_receive().await? {
+                return self.get_received(data).await;
+            }
+        }
+    }
+
+    async fn write_transaction(
+        &mut self,
+        t: &transactions::Transaction,
+    ) -> Result<(), AsyncError<Hal>> {
+        match t.reg() {
+            Some(reg) => self.hal.write_regs(reg, t.data()).await,
+            None => self.hal.write_cmd(t.prefix()[0], t.data()).await,
+        }
+    }
+
+    async fn configure_modem(&mut self, modem: &Modem) -> Result<(), AsyncError<Hal>> {
+        for t in transactions::configure_modem(self.packet_type, modem).iter() {
+            self.write_transaction(t).await?;
+        }
+        self.packet_type = PacketType::from(modem);
+
+        // Apply read-modify-write patches
+        match modem {
+            #[cfg(feature = "modem-flrc")]
+            Modem::Flrc(c) if c.patch_syncword => {
+                let reg = Registers::LrSyncWordTolerance as u16;
+                let mut d = [0u8; 1];
+                self.hal.read_regs(reg, &mut d).await?;
+
+                let d = transactions::patch_flrc_sync_word_tolerance(d[0]);
+                self.hal.write_regs(reg, &[d]).await?;
+            }
+            #[cfg(feature = "modem-lora")]
+            Modem::LoRa(c) => {
+                if let Some(sync_word) = c.sync_word {
+                    let reg = Registers::LrLoRaSyncWordMsb as u16;
+                    let mut d = [0u8; 2];
+                    self.hal.read_regs(reg, &mut d).await?;
+
+                    let d = transactions::patch_lora_sync_word(d, sync_word);
+                    self.hal.write_regs(reg, &d).await?;
+                }
+            }
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    async fn set_standby(&mut self) -> Result<(), AsyncError<Hal>> {
+        self.hal.write_cmd(Commands::SetStandby as u8, &[0]).await
+    }
+
+    async fn set_irq_dio_mask(&mut self, irq: Irq) -> Result<(), AsyncError<Hal>> {
+        let raw = irq.bits();
+
+        self.hal
+            .write_cmd(
+                Commands::SetDioIrqParams as u8,
+                &[
+                    (raw >> 8) as u8,
+                    raw as u8,
+                    (raw >> 8) as u8,
+                    raw as u8,
+                    0,
+                    0,
+                    0,
+                    0,
+                ],
+            )
+            .await
+    }
+
+    async fn set_timeout_cmd(
+        &mut self,
+        command: Commands,
+        timeout: &Timeout,
+    ) -> Result<(), AsyncError<Hal>> {
+        let count = timeout.count();
+
+        self.hal
+            .write_cmd(
+                command as u8,
+                &[timeout.step() as u8, (count >> 8) as u8, count as u8],
+            )
+            .await
+    }
+
+    async fn get_interrupts(&mut self) -> Result<Irq, AsyncError<Hal>> {
+        let mut data = [0u8; 2];
+        self.hal
+            .read_cmd(Commands::GetIrqStatus as u8, &mut data)
+            .await?;
+
+        let irq = Irq::from_bits_truncate((data[0] as u16) << 8 | data[1] as u16);
+        if !irq.is_empty() {
+            self.hal
+                .write_cmd(Commands::ClearIrqStatus as u8, &data)
+                .await?;
+        }
+
+        Ok(irq)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::mock::MockHal;
+
+    /// Poll a future to completion, mock operations never yield
+    #[cfg(feature = "async")]
+    fn block_on<F: core::future::Future>(f: F) -> F::Output {
+        use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+        fn clone(_: *const ()) -> RawWaker {
+            RawWaker::new(core::ptr::null(), &VTABLE)
+        }
+        fn noop(_: *const ()) {}
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+        // Safety: the vtable functions ignore the (null) data pointer
+        let waker = unsafe { Waker::from_raw(clone(core::ptr::null())) };
+
+        let mut f = core::pin::pin!(f);
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(v) = f.as_mut().poll(&mut cx) {
+                return v;
+            }
+        }
+    }
+
+    /// Scripted firmware version read for device initialisation
+    fn firmware_version() -> (Vec<u8>, Vec<u8>) {
+        let reg = Registers::LrFirmwareVersionMsb as u16;
+        (
+            vec![Commands::ReadRegister as u8, (reg >> 8) as u8, reg as u8, 0],
+            vec![0xA9, 0xB5],
+        )
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn async_new() {
+        use crate::asynch::Sx128xAsync;
+
+        // Unknown firmware versions are rejected
+        let hal = MockHal {
+            reads: vec![(firmware_version().0, vec![0x12, 0x34])],
+            ..Default::default()
+        };
+        assert!(matches!(
+            block_on(Sx128xAsync::new(hal, &Config::default())),
+            Err(Error::InvalidDevice(0x1234))
+        ));
+
+        let hal = MockHal {
+            reads: vec![firmware_version()],
+            ..Default::default()
+        };
+        let radio = block_on(Sx128xAsync::new(hal, &Config::default())).unwrap();
+        assert_eq!(
+            radio.hal.written(&[Commands::Calibrate as u8]),
+            vec![vec![CalibrationParams::all().bits()]]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn async_configure() {
+        use crate::asynch::Sx128xAsync;
+        use crate::device::lora::*;
+
+        let reg = |r: Registers| {
+            let r = r as u16;
+            vec![Commands::WiteRegister as u8, (r >> 8) as u8, r as u8]
+        };
+
+        let mut radio = Sx128xAsync::build(MockHal::default());
+        let config = Config {
+            modem: Modem::LoRa(LoRaConfig {
+                sync_word: Some(LoRaSyncWord::Private),
+                ..Default::default()
+            }),
+            channel: Channel::LoRa(LoRaChannel::default()),
+            lna_boost: true,
+            long_preamble: true,
+            auto_fs: true,
+            ..Default::default()
+        };
+        block_on(radio.configure(&config)).unwrap();
+
+        let v = LoRaSyncWord::Private.value();
+        assert_eq!(
+            radio.hal.written(&reg(Registers::LrLoRaSyncWordMsb)),
+            vec![vec![v & 0xF0, (v & 0x0F) << 4]]
+        );
+        assert_eq!(
+            radio.hal.written(&reg(Registers::LnaRegime)),
+            vec![vec![MASK_LNA_REGIME]]
+        );
+        assert_eq!(
+            radio.hal.written(&[Commands::SetLongPreamble as u8]),
+            vec![vec![1]]
+        );
+        assert_eq!(
+            radio.hal.written(&[Commands::SetAutoFs as u8]),
+            vec![vec![1]]
+        );
+
+        // Mismatched modem and channel configurations are rejected
+        let config = Config {
+            modem: Modem::LoRa(LoRaConfig::default()),
+            channel: Channel::Flrc(Default::default()),
+            ..Default::default()
+        };
+        assert_eq!(
+            block_on(radio.configure(&config)),
+            Err(Error::InvalidConfiguration)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn async_transmit_receive() {
+        use crate::asynch::Sx128xAsync;
+
+        let reads = vec![
+            firmware_version(),
+            (
+                vec![Commands::GetIrqStatus as u8, 0],
+                vec![0x00, (Irq::TX_DONE | Irq::RX_DONE).bits() as u8],
+            ),
+            (vec![Commands::GetRxBufferStatus as u8, 0], vec![3, 0x10]),
+            (vec![Commands::ReadBuffer as u8, 0x10, 0], vec![4, 5, 6]),
+        ];
+        let hal = MockHal {
+            reads,
+            ..Default::default()
+        };
+
+        let mut radio = block_on(Sx128xAsync::new(hal, &Config::default())).unwrap();
+
+        block_on(radio.transmit(&[1, 2, 3])).unwrap();
+        assert_eq!(
+            radio.hal.written(&[Commands::WriteBuffer as u8, 0]),
+            vec![vec![1, 2, 3]]
+        );
+
+        let mut buff = [0u8; 8];
+        let (len, _info) = block_on(radio.receive(&mut buff)).unwrap();
+        assert_eq!(&buff[..len], &[4, 5, 6]);
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn async_packet_status() {
+        use crate::asynch::Sx128xAsync;
+
+        let mut radio = Sx128xAsync::build(MockHal::default());
+        radio.packet_type = PacketType::Gfsk;
+        radio.hal.reads = vec![
+            (vec![Commands::GetRxBufferStatus as u8, 0], vec![2, 0]),
+            (
+                vec![Commands::GetPacketStatus as u8, 0],
+                vec![0, 0, PacketStatus::CRC_ERROR.bits(), 0, 0],
+            ),
+        ];
+
+        let mut buff = [0u8; 4];
+        assert!(matches!(
+            block_on(radio.get_received(&mut buff)),
+            Err(Error::InvalidCrc)
+        ));
+
+        // CRC errors are flagged when delivered
+        radio.config.deliver_crc_errors = true;
+        let (_, info) = block_on(radio.get_received(&mut buff)).unwrap();
+        assert!(!info.crc_ok);
+
+        // Checks may be disabled to inspect bad frames
+        radio.config.ignore_packet_status = true;
+        radio.config.deliver_crc_errors = false;
+        assert!(block_on(radio.get_received(&mut buff)).is_ok());
+    }
+}
@@ -0,0 +1,101 @@
+//! Randomised backoff for listen-before-talk / CSMA style channel access
+//!
+//! Backoff-using methods accept any [`BackoffRng`], which is implemented for
+//! `rand_core::RngCore` sources when the `rand_core` feature is enabled.
+//! For platforms without an RNG the [`Lcg`] fallback may be seeded with a
+//! user-provided value (for example a device serial number).
+//!
+//! Note that the LCG fallback is _not_ cryptographically secure and has poor
+//! low-order bit quality, which is sufficient to de-synchronise contending
+//! devices as long as each device uses a distinct seed. A given seed always
+//! produces the same backoff sequence, which is useful for testing.
+
+/// Source of randomness for backoff calculations
+pub trait BackoffRng {
+    /// Fetch the next random value
+    fn next_u32(&mut self) -> u32;
+}
+
+#[cfg(feature = "rand_core")]
+impl<R: rand_core::RngCore> BackoffRng for R {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::RngCore::next_u32(self)
+    }
+}
+
+/// Simple linear congruential generator for environments without an RNG
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Lcg {
+    state: u32,
+}
+
+impl Lcg {
+    /// Create a new generator from the provided seed
+    pub fn new(seed: u32) -> Self {
+        Self { state: seed }
+    }
+}
+
+#[cfg(not(feature = "rand_core"))]
+impl BackoffRng for Lcg {
+    fn next_u32(&mut self) -> u32 {
+        self.step()
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl rand_core::RngCore for Lcg {
+    fn next_u32(&mut self) -> u32 {
+        self.step()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_u32(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        rand_core::impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl Lcg {
+    fn step(&mut self) -> u32 {
+        // Numerical Recipes constants, the low bits of a power-of-two modulus
+        // LCG are poorly distributed so the upper bits are rotated down
+        self.state = self
+            .state
+            .wrapping_mul(1_664_525)
+            .wrapping_add(1_013_904_223);
+        self.state.rotate_left(16)
+    }
+}
+
+/// Compute a binary exponential backoff in microseconds for the provided attempt,
+/// uniformly selecting between 0 and `2^min(attempt, max_exponent) - 1` slots
+pub fn backoff_us<R: BackoffRng>(rng: &mut R, attempt: u8, max_exponent: u8, slot_us: u32) -> u32 {
+    let exponent = core::cmp::min(core::cmp::min(attempt, max_exponent), 31);
+    let slots = rng.next_u32() % (1u32 << exponent);
+
+    slots.saturating_mul(slot_us)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_deterministic_seed() {
+        let (mut a, mut b) = (Lcg::new(0x1234), Lcg::new(0x1234));
+
+        for attempt in 0..10 {
+            let d = backoff_us(&mut a, attempt, 6, 100);
+            assert_eq!(d, backoff_us(&mut b, attempt, 6, 100));
+            assert!(d < (1 << attempt.min(6)) * 100);
+        }
+    }
+}
@@ -2,7 +2,7 @@
 
 use core::fmt::Debug;
 
-use log::{error, trace};
+use log::{error, trace, warn};
 
 use embedded_hal::{
     delay::DelayNs,
@@ -20,12 +20,30 @@ pub trait Hal {
     /// Reset the device
     fn reset(&mut self) -> Result<(), Error<Self::CommsError, Self::PinError>>;
 
+    /// Reset the device and wait for the busy pin to de-assert, indicating the
+    /// device is ready, returning `Error::BusyTimeout` if this does not occur
+    /// within the busy timeout
+    fn reset_and_wait_ready(&mut self) -> Result<(), Error<Self::CommsError, Self::PinError>> {
+        self.reset()?;
+        self.wait_busy()
+    }
+
     /// Fetch radio device busy pin value
     fn get_busy(&mut self) -> Result<PinState, Error<Self::CommsError, Self::PinError>>;
 
     /// Fetch radio device ready / irq (DIO) pin value
     fn get_dio(&mut self) -> Result<PinState, Error<Self::CommsError, Self::PinError>>;
 
+    /// Check whether DIO has been asserted since the last call
+    ///
+    /// The default implementation samples the DIO level, so a brief pulse
+    /// between polls (for example where the IRQ line is shared or has already
+    /// been serviced) is missed. Implementations with edge-triggered pin
+    /// interrupts should override this to return and clear a latched edge.
+    fn dio_asserted(&mut self) -> Result<bool, Error<Self::CommsError, Self::PinError>> {
+        Ok(self.get_dio()? == PinState::High)
+    }
+
     /// Delay for the specified time
     fn delay_ms(&mut self, ms: u32);
 
@@ -77,18 +95,37 @@ pub trait Hal {
         data: &mut [u8],
     ) -> Result<(), Error<Self::CommsError, Self::PinError>>;
 
+    /// Fetch the timeout awaiting busy pin de-assert in milliseconds
+    fn busy_timeout_ms(&self) -> u32 {
+        BUSY_TIMEOUT_MS
+    }
+
+    /// Set the timeout awaiting busy pin de-assert in milliseconds,
+    /// this is ignored by implementations using a fixed timeout
+    fn set_busy_timeout(&mut self, _timeout_ms: u32) {}
+
+    /// Poll the radio device busy pin once without blocking,
+    /// returning true if the device is ready to accept commands
+    fn try_wait_busy(&mut self) -> Result<bool, Error<Self::CommsError, Self::PinError>> {
+        Ok(self.get_busy()? == PinState::Low)
+    }
+
     /// Wait on radio device busy
+    ///
+    /// With a busy timeout of zero this does not block, returning
+    /// `Error::BusyTimeout` immediately if the device is busy
     fn wait_busy(&mut self) -> Result<(), Error<Self::CommsError, Self::PinError>> {
-        // TODO: timeouts here
-        let mut timeout = 0;
-        while self.get_busy()? == PinState::High {
-            self.delay_ms(1);
-            timeout += 1;
+        let busy_timeout_ms = self.busy_timeout_ms();
 
-            if timeout > BUSY_TIMEOUT_MS {
-                error!("Busy timeout after {} ms", BUSY_TIMEOUT_MS);
+        let mut timeout = 0;
+        while !self.try_wait_busy()? {
+            if timeout >= busy_timeout_ms {
+                error!("Busy timeout after {} ms", busy_timeout_ms);
                 return Err(Error::BusyTimeout);
             }
+
+            self.delay_ms(1);
+            timeout += 1;
         }
 
         Ok(())
@@ -137,6 +174,34 @@ pub trait Hal {
     ) -> Result<(), Error<Self::CommsError, Self::PinError>>;
 }
 
+/// Wait for the device to become ready prior to issuing a command, retrying
+/// the busy wait up to `retries` times on `Error::BusyTimeout`
+///
+/// Only this wait is retried, as a timeout following a write cannot distinguish
+/// a slow command from one that was not received, and re-sending commands such
+/// as `SetTx` or `SetFs` is not safe
+pub fn wait_busy_retries<H>(
+    hal: &mut H,
+    retries: usize,
+) -> Result<(), Error<H::CommsError, H::PinError>>
+where
+    H: Hal,
+{
+    let mut attempt = 0;
+
+    loop {
+        match hal.wait_busy() {
+            Err(Error::BusyTimeout) if attempt < retries => {
+                attempt += 1;
+                warn!("Busy timeout, retrying ({}/{})", attempt, retries);
+
+                hal.delay_ms(1);
+            }
+            r => return r,
+        }
+    }
+}
+
 pub trait HalError {
     type E: Debug;
 }
@@ -161,6 +226,42 @@ pub struct Base<
     pub ready: Ready,
     pub sdn: Sdn,
     pub delay: Delay,
+    /// Timeout awaiting busy pin de-assert in milliseconds
+    busy_timeout_ms: u32,
+    /// Device reset timing
+    reset_timing: ResetTiming,
+}
+
+impl<Spi, Busy, Ready, Sdn, Delay> Base<Spi, Busy, Ready, Sdn, Delay>
+where
+    Spi: SpiDevice<u8>,
+    Busy: InputPin,
+    Ready: InputPin,
+    Sdn: OutputPin,
+    Delay: DelayNs,
+{
+    /// Create a new base interface with the default busy timeout and reset timing
+    pub fn new(spi: Spi, busy: Busy, ready: Ready, sdn: Sdn, delay: Delay) -> Self {
+        Self {
+            spi,
+            busy,
+            ready,
+            sdn,
+            delay,
+            busy_timeout_ms: BUSY_TIMEOUT_MS,
+            reset_timing: ResetTiming::default(),
+        }
+    }
+
+    /// Fetch the device reset timing
+    pub fn reset_timing(&self) -> ResetTiming {
+        self.reset_timing
+    }
+
+    /// Set the device reset timing
+    pub fn set_reset_timing(&mut self, timing: ResetTiming) {
+        self.reset_timing = timing;
+    }
 }
 
 impl<Spi, Busy, Ready, Sdn, PinError, Delay> Hal for Base<Spi, Busy, Ready, Sdn, Delay>
@@ -180,19 +281,29 @@ where
 
     /// Reset the radio
     fn reset(&mut self) -> Result<(), Error<Self::CommsError, Self::PinError>> {
-        self.delay_ms(20);
+        let t = self.reset_timing;
+
+        self.delay_ms(t.pre_ms);
 
         self.sdn.set_low().map_err(Error::Pin)?;
 
-        self.delay_ms(50);
+        self.delay_ms(t.low_ms);
 
         self.sdn.set_high().map_err(Error::Pin)?;
 
-        self.delay_ms(20);
+        self.delay_ms(t.post_ms);
 
         Ok(())
     }
 
+    fn busy_timeout_ms(&self) -> u32 {
+        self.busy_timeout_ms
+    }
+
+    fn set_busy_timeout(&mut self, timeout_ms: u32) {
+        self.busy_timeout_ms = timeout_ms;
+    }
+
     fn get_busy(&mut self) -> Result<PinState, Error<Self::CommsError, Self::PinError>> {
         match self.busy.is_high().map_err(Error::Pin)? {
             true => Ok(PinState::High),
@@ -372,3 +483,190 @@ where
         r
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use core::{cell::RefCell, convert::Infallible};
+    use std::{rc::Rc, vec::Vec};
+
+    use embedded_hal::digital;
+    use embedded_hal_bus::spi::RefCellDevice;
+
+    use super::*;
+    use crate::{mock::*, Sx128x, NUM_RETRIES};
+
+    #[test]
+    fn shared_bus() {
+        let log = BusLog::default();
+        let bus = RefCell::new(MockBus(log.clone()));
+
+        let mut radios: Vec<_> = (1..=2)
+            .map(|id| {
+                Sx128x::build(Base::new(
+                    RefCellDevice::new(&bus, MockCs(id, log.clone()), MockDelay),
+                    MockPin,
+                    MockPin,
+                    MockPin,
+                    MockDelay,
+                ))
+            })
+            .collect();
+        log.borrow_mut().clear();
+
+        radios[0].set_frequency(2_400_000_000).unwrap();
+        radios[1].set_frequency(2_480_000_000).unwrap();
+
+        // Each transaction is issued with only the matching radio selected
+        let t1 = transactions::set_frequency(&radios[0].config, 2_400_000_000);
+        let t2 = transactions::set_frequency(&radios[1].config, 2_480_000_000);
+        assert_eq!(
+            *log.borrow(),
+            vec![
+                BusEvent::Select(1),
+                BusEvent::Write(t1.prefix().to_vec()),
+                BusEvent::Write(t1.data().to_vec()),
+                BusEvent::Deselect(1),
+                BusEvent::Select(2),
+                BusEvent::Write(t2.prefix().to_vec()),
+                BusEvent::Write(t2.data().to_vec()),
+                BusEvent::Deselect(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn busy_timeout() {
+        struct BusyPin(Rc<RefCell<u32>>);
+
+        impl digital::ErrorType for BusyPin {
+            type Error = Infallible;
+        }
+
+        impl InputPin for BusyPin {
+            fn is_high(&mut self) -> Result<bool, Infallible> {
+                *self.0.borrow_mut() += 1;
+                Ok(true)
+            }
+
+            fn is_low(&mut self) -> Result<bool, Infallible> {
+                Ok(false)
+            }
+        }
+
+        let polls = Rc::new(RefCell::new(0));
+        let bus = RefCell::new(MockBus(BusLog::default()));
+        let mut radio = Sx128x::build(Base::new(
+            RefCellDevice::new(&bus, MockCs(1, BusLog::default()), MockDelay),
+            BusyPin(polls.clone()),
+            MockPin,
+            MockPin,
+            MockDelay,
+        ));
+
+        let config = Config {
+            busy_timeout_ms: 10,
+            ..Default::default()
+        };
+        assert_eq!(radio.configure(&config), Err(Error::BusyTimeout));
+        assert_eq!(*polls.borrow(), 11 * (NUM_RETRIES as u32 + 1));
+
+        // Without retries the command fails on the first timeout
+        *polls.borrow_mut() = 0;
+        let config = Config {
+            command_retries: 0,
+            ..config
+        };
+        assert_eq!(radio.configure(&config), Err(Error::BusyTimeout));
+        assert_eq!(*polls.borrow(), 11);
+    }
+
+    #[test]
+    fn busy_timeout_no_resend() {
+        // Busy is released for the first `ready` polls, then remains asserted
+        struct BusyPin(Rc<RefCell<u32>>, u32);
+
+        impl digital::ErrorType for BusyPin {
+            type Error = Infallible;
+        }
+
+        impl InputPin for BusyPin {
+            fn is_high(&mut self) -> Result<bool, Infallible> {
+                *self.0.borrow_mut() += 1;
+                Ok(*self.0.borrow() > self.1)
+            }
+
+            fn is_low(&mut self) -> Result<bool, Infallible> {
+                self.is_high().map(|v| !v)
+            }
+        }
+
+        let log = BusLog::default();
+        let polls = Rc::new(RefCell::new(0));
+        let bus = RefCell::new(MockBus(log.clone()));
+        let mut radio = Sx128x::build(Base::new(
+            RefCellDevice::new(&bus, MockCs(1, log.clone()), MockDelay),
+            BusyPin(polls.clone(), 1),
+            MockPin,
+            MockPin,
+            MockDelay,
+        ));
+        radio.hal.set_busy_timeout(10);
+
+        // A timeout following the write returns without re-sending the command
+        assert_eq!(
+            radio::State::set_state(&mut radio, State::Fs),
+            Err(Error::BusyTimeout)
+        );
+        let writes = |log: &BusLog| {
+            log.borrow()
+                .iter()
+                .filter(|e| **e == BusEvent::Write(vec![Commands::SetFs as u8]))
+                .count()
+        };
+        assert_eq!(writes(&log), 1);
+
+        // SetSleep does not await the device following the command
+        *polls.borrow_mut() = 0;
+        log.borrow_mut().clear();
+        radio.sleep(SleepConfig::empty()).unwrap();
+        assert_eq!(*polls.borrow(), 1);
+    }
+
+    #[test]
+    fn reset_timing() {
+        struct RecordingDelay(Rc<RefCell<Vec<u32>>>);
+
+        impl DelayNs for RecordingDelay {
+            fn delay_ns(&mut self, _ns: u32) {}
+
+            fn delay_ms(&mut self, ms: u32) {
+                self.0.borrow_mut().push(ms);
+            }
+        }
+
+        let delays = Rc::new(RefCell::new(vec![]));
+        let bus = RefCell::new(MockBus(BusLog::default()));
+        let mut hal = Base::new(
+            RefCellDevice::new(&bus, MockCs(1, BusLog::default()), MockDelay),
+            MockPin,
+            MockPin,
+            MockPin,
+            RecordingDelay(delays.clone()),
+        );
+
+        hal.reset().unwrap();
+        assert_eq!(
+            *delays.borrow(),
+            vec![RESET_PRE_MS, RESET_LOW_MS, RESET_POST_MS]
+        );
+
+        delays.borrow_mut().clear();
+        hal.set_reset_timing(ResetTiming {
+            pre_ms: 1,
+            low_ms: 2,
+            post_ms: 3,
+        });
+        hal.reset().unwrap();
+        assert_eq!(*delays.borrow(), vec![1, 2, 3]);
+    }
+}
@@ -17,6 +17,17 @@ pub struct BleChannel {
     pub ms: ModShaping,
 }
 
+impl Default for BleChannel {
+    fn default() -> Self {
+        Self {
+            freq: 2_440_000_000,
+            br_bw: GfskBleBitrateBandwidth::BR_1_000_BW_1_2,
+            mi: GfskBleModIndex::MOD_IND_0_50,
+            ms: ModShaping::Bt0_5,
+        }
+    }
+}
+
 /// BLE operating mode packet configuration
 #[derive(Clone, PartialEq, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -32,6 +43,17 @@ pub struct BleConfig {
     pub whitening: WhiteningModes,
 }
 
+impl Default for BleConfig {
+    fn default() -> Self {
+        Self {
+            connection_state: BleConnectionStates::BLE_PAYLOAD_LENGTH_MAX_37_BYTES,
+            crc_field: BleCrcFields::BLE_CRC_3B,
+            packet_type: BlePacketTypes::BLE_PRBS_9,
+            whitening: WhiteningModes::RADIO_WHITENING_ON,
+        }
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -79,3 +101,50 @@ pub enum BlePacketTypes {
     /// Repeated '00000000' sequence
     BLE_ALL_0 = 0x14,
 }
+
+/// BLE PDU header length in bytes
+pub const BLE_PDU_HEADER_LEN: usize = 2;
+
+impl BleConnectionStates {
+    /// Fetch the maximum PDU payload length in bytes, excluding the PDU header
+    pub fn max_payload_len(&self) -> usize {
+        match self {
+            BleConnectionStates::BLE_PAYLOAD_LENGTH_MAX_31_BYTES => 31,
+            BleConnectionStates::BLE_PAYLOAD_LENGTH_MAX_37_BYTES => 37,
+            BleConnectionStates::BLE_TX_TEST_MODE => 37,
+            BleConnectionStates::BLE_PAYLOAD_LENGTH_MAX_255_BYTES => 255,
+        }
+    }
+}
+
+/// BLE PDU header, received ahead of the PDU payload
+///
+/// For advertising channel PDUs the first byte contains the PDU type (bits 0..3),
+/// RFU (bit 4), ChSel (bit 5), TxAdd (bit 6) and RxAdd (bit 7) fields, the second
+/// byte contains the payload length.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BlePduHeader {
+    /// Raw header byte
+    pub header: u8,
+    /// Payload length byte
+    pub length: u8,
+}
+
+impl BlePduHeader {
+    /// Fetch the PDU type from the header byte
+    pub fn pdu_type(&self) -> u8 {
+        self.header & 0x0F
+    }
+
+    /// Fetch the TxAdd (transmitter address random) flag
+    pub fn tx_add(&self) -> bool {
+        self.header & 0x40 != 0
+    }
+
+    /// Fetch the RxAdd (receiver address random) flag
+    pub fn rx_add(&self) -> bool {
+        self.header & 0x80 != 0
+    }
+}
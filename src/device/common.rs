@@ -33,6 +33,28 @@ pub enum PreambleLength {
     PreambleLength32 = 0x70,
 }
 
+impl PreambleLength {
+    /// Fetch the preamble length for a length in bits, returning `None` for
+    /// lengths unsupported by the hardware (4 to 32 bits in steps of 4)
+    pub fn from_bits(bits: u16) -> Option<Self> {
+        use PreambleLength::*;
+
+        let v = match bits {
+            4 => PreambleLength04,
+            8 => PreambleLength08,
+            12 => PreambleLength12,
+            16 => PreambleLength16,
+            20 => PreambleLength20,
+            24 => PreambleLength24,
+            28 => PreambleLength28,
+            32 => PreambleLength32,
+            _ => return None,
+        };
+
+        Some(v)
+    }
+}
+
 /// Bitrate-Bandwidth for GFSK and BLE modes
 #[derive(Copy, Clone, PartialEq, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -67,6 +89,36 @@ pub enum GfskBleBitrateBandwidth {
     BR_0_125_BW_0_3 = 0xEF,
 }
 
+impl GfskBleBitrateBandwidth {
+    /// Fetch the bandwidth in Hz for a given bitrate-bandwidth configuration
+    pub fn bandwidth_hz(&self) -> u32 {
+        use GfskBleBitrateBandwidth::*;
+
+        match self {
+            BR_2_000_BW_2_4 | BR_1_600_BW_2_4 | BR_1_000_BW_2_4 | BR_0_800_BW_2_4 => 2_400_000,
+            BR_1_000_BW_1_2 | BR_0_800_BW_1_2 | BR_0_500_BW_1_2 | BR_0_400_BW_1_2 => 1_200_000,
+            BR_0_500_BW_0_6 | BR_0_400_BW_0_6 | BR_0_250_BW_0_6 => 600_000,
+            BR_0_250_BW_0_3 | BR_0_125_BW_0_3 => 300_000,
+        }
+    }
+
+    /// Fetch the raw bitrate in bits per second for a given bitrate-bandwidth configuration
+    pub fn bitrate_bps(&self) -> u32 {
+        use GfskBleBitrateBandwidth::*;
+
+        match self {
+            BR_2_000_BW_2_4 => 2_000_000,
+            BR_1_600_BW_2_4 => 1_600_000,
+            BR_1_000_BW_2_4 | BR_1_000_BW_1_2 => 1_000_000,
+            BR_0_800_BW_2_4 | BR_0_800_BW_1_2 => 800_000,
+            BR_0_500_BW_1_2 | BR_0_500_BW_0_6 => 500_000,
+            BR_0_400_BW_1_2 | BR_0_400_BW_0_6 => 400_000,
+            BR_0_250_BW_0_6 | BR_0_250_BW_0_3 => 250_000,
+            BR_0_125_BW_0_3 => 125_000,
+        }
+    }
+}
+
 /// Modulation Index for GFSK and BLE modes
 #[derive(Copy, Clone, PartialEq, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -2,6 +2,9 @@
 
 use super::common::*;
 
+/// Maximum FLRC payload length in bytes
+pub const FLRC_MAX_PAYLOAD_LEN: usize = 127;
+
 /// FLRC configuration structure
 #[derive(Clone, PartialEq, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -39,6 +42,7 @@ pub struct FlrcConfig {
     pub header_type: GfskFlrcPacketLength,
     pub payload_length: u8,
     pub crc_mode: GfskFlrcCrcModes,
+    /// Packet whitening, seeded by `Sx128x::set_whitening_seed` when enabled
     pub whitening: WhiteningModes,
 
     /// Patch to resolver errata 16.4, increased PER in FLRC packets with syncword
@@ -84,6 +88,37 @@ pub enum FlrcBitrate {
     BR_0_260_BW_0_3 = 0xEB,
 }
 
+impl FlrcBitrate {
+    /// Fetch the bandwidth in Hz for a given bitrate-bandwidth configuration
+    pub fn bandwidth_hz(&self) -> u32 {
+        use FlrcBitrate::*;
+
+        match self {
+            BR_2_600_BW_2_4 | BR_2_080_BW_2_4 => 2_400_000,
+            BR_1_300_BW_1_2 | BR_1_040_BW_1_2 => 1_200_000,
+            BR_0_650_BW_0_6 | BR_0_520_BW_0_6 => 600_000,
+            BR_0_325_BW_0_3 | BR_0_260_BW_0_3 => 300_000,
+        }
+    }
+
+    /// Fetch the raw bitrate in bits per second for a given bitrate-bandwidth
+    /// configuration, prior to coding
+    pub fn bitrate_bps(&self) -> u32 {
+        use FlrcBitrate::*;
+
+        match self {
+            BR_2_600_BW_2_4 => 2_600_000,
+            BR_2_080_BW_2_4 => 2_080_000,
+            BR_1_300_BW_1_2 => 1_300_000,
+            BR_1_040_BW_1_2 => 1_040_000,
+            BR_0_650_BW_0_6 => 650_000,
+            BR_0_520_BW_0_6 => 520_000,
+            BR_0_325_BW_0_3 => 325_000,
+            BR_0_260_BW_0_3 => 260_000,
+        }
+    }
+}
+
 #[cfg(feature = "util")]
 const FLRC_BIT_RATE_PARSE_ERR: &str = "Invalid FLRC bitrate bandwidth (supported options: 2600_2400, 2080_2400, 1300_1200, 1040_1200, 650_600, 520_600, 325_300, 260_300)";
 
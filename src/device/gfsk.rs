@@ -45,7 +45,7 @@ pub struct GfskConfig {
     pub payload_length: u8,
     /// CRC mode
     pub crc_mode: GfskFlrcCrcModes,
-    /// Packet whitening
+    /// Packet whitening, seeded by `Sx128x::set_whitening_seed` when enabled
     pub whitening: WhiteningModes,
 
     /// Patch to set "Packet Preamble Settings" register 0x09C1 with preamble
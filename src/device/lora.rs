@@ -15,6 +15,8 @@ pub struct LoRaConfig {
     pub crc_mode: LoRaCrc,
     /// IQ inversion configuration (defaults to disabled)
     pub invert_iq: LoRaIq,
+    /// Sync word configuration (defaults to the device default if unset)
+    pub sync_word: Option<LoRaSyncWord>,
 }
 
 impl Default for LoRaConfig {
@@ -25,6 +27,34 @@ impl Default for LoRaConfig {
             payload_length: 255,
             crc_mode: LoRaCrc::Enabled,
             invert_iq: LoRaIq::Inverted,
+            sync_word: None,
+        }
+    }
+}
+
+/// LoRa sync word presets
+///
+/// By convention public networks (such as LoRaWAN) use `0x34` and private
+/// networks `0x12`, devices only receive packets with a matching sync word
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LoRaSyncWord {
+    /// Public network sync word (0x34)
+    Public,
+    /// Private network sync word (0x12)
+    Private,
+    /// Custom sync word
+    Custom(u8),
+}
+
+impl LoRaSyncWord {
+    /// Fetch the raw sync word value
+    pub fn value(&self) -> u8 {
+        match self {
+            LoRaSyncWord::Public => 0x34,
+            LoRaSyncWord::Private => 0x12,
+            LoRaSyncWord::Custom(v) => *v,
         }
     }
 }
@@ -55,6 +85,55 @@ impl Default for LoRaChannel {
     }
 }
 
+impl LoRaChannel {
+    /// Compute the time on air of a packet in microseconds, per the datasheet
+    /// packet duration formulas.
+    ///
+    /// SF11 and SF12 use the low data rate optimised payload symbol count, and
+    /// long interleaved coding rates are approximated by their equivalent
+    /// (non-interleaved) coding rate
+    pub fn time_on_air_us(
+        &self,
+        payload_len: u8,
+        preamble_len: u8,
+        header: LoRaHeader,
+        crc: LoRaCrc,
+    ) -> u32 {
+        let sf = (self.sf as u8 >> 4) as i32;
+        let cr = (self.cr as u8 & 0x03) as i32;
+        let cr = if cr == 0 { 4 } else { cr };
+
+        let h = (header == LoRaHeader::Explicit) as i32;
+        let c = (crc == LoRaCrc::Enabled) as i32;
+
+        // Payload bits and bits per symbol, by SF range
+        let (bits, sym_bits, sync_quarters) = match sf {
+            5 | 6 => (
+                8 * payload_len as i32 + 16 * c - 4 * sf + 20 * h,
+                4 * sf,
+                25,
+            ),
+            7..=10 => (
+                8 * payload_len as i32 + 16 * c - 4 * sf + 8 + 20 * h,
+                4 * sf,
+                17,
+            ),
+            _ => (
+                8 * payload_len as i32 + 16 * c - 4 * sf + 8 + 20 * h,
+                4 * (sf - 2),
+                17,
+            ),
+        };
+
+        let payload_symbols = 8 + (bits.max(0) + sym_bits - 1) / sym_bits * (cr + 4);
+
+        // Total duration in quarter symbols to account for the fractional sync
+        let quarters = preamble_len as u64 * 4 + sync_quarters + payload_symbols as u64 * 4;
+
+        (quarters * (1u64 << sf) * 1_000_000 / (4 * self.bw.get_bw_hz() as u64)) as u32
+    }
+}
+
 /// Spreading factor for LoRa mode
 #[derive(Copy, Clone, PartialEq, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -70,6 +149,33 @@ pub enum LoRaSpreadingFactor {
     Sf12 = 0xC0,
 }
 
+#[cfg(feature = "util")]
+const LORA_SF_PARSE_ERR: &str =
+    "Invalid spreading factor (supported options: 5, 6, 7, 8, 9, 10, 11, 12)";
+
+#[cfg(feature = "util")]
+impl std::str::FromStr for LoRaSpreadingFactor {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use self::LoRaSpreadingFactor::*;
+
+        let v = match s {
+            "5" => Sf5,
+            "6" => Sf6,
+            "7" => Sf7,
+            "8" => Sf8,
+            "9" => Sf9,
+            "10" => Sf10,
+            "11" => Sf11,
+            "12" => Sf12,
+            _ => return Err(LORA_SF_PARSE_ERR),
+        };
+
+        Ok(v)
+    }
+}
+
 /// Bandwidth for LoRa mode
 #[derive(Copy, Clone, PartialEq, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -85,6 +191,26 @@ pub enum LoRaBandwidth {
     Bw1600kHz = 0x0A,
 }
 
+#[cfg(feature = "util")]
+const LORA_BW_PARSE_ERR: &str = "Invalid bandwidth in kHz (supported options: 200, 400, 800, 1600)";
+
+#[cfg(feature = "util")]
+impl std::str::FromStr for LoRaBandwidth {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let v = match s {
+            "200" => LoRaBandwidth::Bw200kHz,
+            "400" => LoRaBandwidth::Bw400kHz,
+            "800" => LoRaBandwidth::Bw800kHz,
+            "1600" => LoRaBandwidth::Bw1600kHz,
+            _ => return Err(LORA_BW_PARSE_ERR),
+        };
+
+        Ok(v)
+    }
+}
+
 impl LoRaBandwidth {
     /// Fetch the bandwidth in Hz for a given bandwidth configuration
     pub fn get_bw_hz(&self) -> u32 {
@@ -95,6 +221,23 @@ impl LoRaBandwidth {
             LoRaBandwidth::Bw1600kHz => 1625000,
         }
     }
+
+    /// Fetch the spreading factors valid for use with this bandwidth
+    ///
+    /// The SX128x datasheet permits every spreading factor (SF5 to SF12)
+    /// at each LoRa bandwidth, this is provided for tooling and validation
+    pub fn valid_spreading_factors(&self) -> &'static [LoRaSpreadingFactor] {
+        use LoRaSpreadingFactor::*;
+
+        const ALL: &[LoRaSpreadingFactor] = &[Sf5, Sf6, Sf7, Sf8, Sf9, Sf10, Sf11, Sf12];
+
+        match self {
+            LoRaBandwidth::Bw200kHz
+            | LoRaBandwidth::Bw400kHz
+            | LoRaBandwidth::Bw800kHz
+            | LoRaBandwidth::Bw1600kHz => ALL,
+        }
+    }
 }
 
 /// Coding rates for LoRa mode
@@ -116,6 +259,37 @@ pub enum LoRaCodingRate {
     CrLI_4_7 = 0x07,
 }
 
+impl core::convert::TryFrom<u8> for LoRaCodingRate {
+    type Error = ();
+
+    fn try_from(v: u8) -> Result<LoRaCodingRate, ()> {
+        match v {
+            0x01 => Ok(LoRaCodingRate::Cr4_5),
+            0x02 => Ok(LoRaCodingRate::Cr4_6),
+            0x03 => Ok(LoRaCodingRate::Cr4_7),
+            0x04 => Ok(LoRaCodingRate::Cr4_8),
+            0x05 => Ok(LoRaCodingRate::CrLI_4_5),
+            0x06 => Ok(LoRaCodingRate::CrLI_4_6),
+            0x07 => Ok(LoRaCodingRate::CrLI_4_7),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Decoded explicit LoRa header, available following `Irq::HEADER_VALID`
+/// and prior to reception of the full payload
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct HeaderInfo {
+    /// Payload length in bytes
+    pub length: u8,
+    /// Payload coding rate (`None` if not recognised)
+    pub coding_rate: Option<LoRaCodingRate>,
+    /// Payload CRC present
+    pub crc: bool,
+}
+
 /// CRC mode for LoRa packet types
 #[derive(Copy, Clone, PartialEq, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -144,3 +318,34 @@ pub enum LoRaHeader {
     /// Constant length packets, no length header included in packet
     Implicit = 0x80,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lora_time_on_air() {
+        let ch = |sf, bw| LoRaChannel {
+            sf,
+            bw,
+            ..Default::default()
+        };
+        let toa = |c: LoRaChannel, header| c.time_on_air_us(10, 12, header, LoRaCrc::Enabled);
+
+        use LoRaBandwidth::*;
+        use LoRaSpreadingFactor::*;
+
+        // SF5: 12 + 6.25 + 33 symbols of 19.69 us
+        assert_eq!(toa(ch(Sf5, Bw1600kHz), LoRaHeader::Explicit), 1009);
+        // SF7: 12 + 4.25 + 28 symbols of 157.54 us
+        assert_eq!(toa(ch(Sf7, Bw800kHz), LoRaHeader::Explicit), 6971);
+        // SF12 (low data rate optimised): 12 + 4.25 + 18 symbols of 20.16 ms
+        assert_eq!(toa(ch(Sf12, Bw200kHz), LoRaHeader::Explicit), 690_648);
+
+        // Implicit headers shorten the payload
+        assert!(
+            toa(ch(Sf7, Bw800kHz), LoRaHeader::Implicit)
+                < toa(ch(Sf7, Bw800kHz), LoRaHeader::Explicit)
+        );
+    }
+}
@@ -1,32 +1,124 @@
 #![allow(non_snake_case, non_camel_case_types, non_upper_case_globals)]
 
+use core::fmt::Debug;
+
 use bitflags::bitflags;
 use log::error;
 
+#[cfg(feature = "modem-ble")]
 pub mod ble;
+#[cfg(feature = "modem-ble")]
 use ble::{BleChannel, BleConfig};
+#[cfg(feature = "modem-flrc")]
 pub mod flrc;
+#[cfg(feature = "modem-flrc")]
 use flrc::{FlrcChannel, FlrcConfig};
+#[cfg(feature = "modem-gfsk")]
 pub mod gfsk;
+#[cfg(feature = "modem-gfsk")]
 use gfsk::{GfskChannel, GfskConfig};
+#[cfg(feature = "modem-lora")]
 pub mod lora;
+#[cfg(feature = "modem-lora")]
 use lora::{LoRaChannel, LoRaConfig};
 
 pub mod common;
 
+pub mod transactions;
+
 pub const BUSY_TIMEOUT_MS: u32 = 500;
 
+/// Default delay prior to asserting reset (SDN low) in milliseconds
+pub const RESET_PRE_MS: u32 = 20;
+
+/// Default reset (SDN low) duration in milliseconds
+pub const RESET_LOW_MS: u32 = 50;
+
+/// Default delay following reset (SDN high) in milliseconds
+pub const RESET_POST_MS: u32 = 20;
+
+/// Device reset timing, see `Config::reset_timing`
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ResetTiming {
+    /// Delay prior to asserting reset (SDN low) in milliseconds
+    pub pre_ms: u32,
+    /// Reset (SDN low) duration in milliseconds
+    pub low_ms: u32,
+    /// Delay following reset (SDN high) in milliseconds
+    pub post_ms: u32,
+}
+
+impl Default for ResetTiming {
+    fn default() -> Self {
+        Self {
+            pre_ms: RESET_PRE_MS,
+            low_ms: RESET_LOW_MS,
+            post_ms: RESET_POST_MS,
+        }
+    }
+}
+
+/// Number of IRQ status reads retained in the IRQ history
+#[cfg(feature = "irq-history")]
+pub const IRQ_HISTORY_LEN: usize = 16;
+
+/// Recovery policy for a device reporting invalid circuit states, which
+/// indicates a wedged or mis-clocked chip (for example following ESD events)
+///
+/// Where `threshold` `Error::InvalidCircuitState` errors occur within a window
+/// of `window` status reads recovery is flagged as pending, and the device is
+/// reset and the configuration restored on calling `Sx128x::recover`
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CircuitStateRecovery {
+    /// Number of invalid circuit states required to trigger recovery
+    pub threshold: u32,
+    /// Window in status reads, starting from the first invalid circuit state
+    pub window: u32,
+}
+
+/// Known device firmware versions
+pub const FIRMWARE_VERSIONS: &[u16] = &[0xA9B5, 0x8E8E];
+
+/// Current `Config` schema version, incremented on incompatible changes
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Schema version assumed for configurations without a `version` field
+#[cfg(feature = "serde")]
+fn legacy_config_version() -> u32 {
+    1
+}
+
 /// Sx128x general configuration object
+///
+/// `modem` and `channel` configurations must match, see `Config::builder`
+/// to construct configurations with matched modem and channel pairs
+///
+/// Fields missing when deserializing are filled from `Config::default()`
 #[derive(Clone, PartialEq, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Config {
+    /// Configuration schema version, see `CONFIG_VERSION`
+    ///
+    /// Configurations without a version predate versioning, so deserialize as
+    /// version 1 rather than the current version
+    #[cfg_attr(feature = "serde", serde(default = "legacy_config_version"))]
+    pub version: u32,
+
     /// Regulator mode configuration
     pub regulator_mode: RegulatorMode,
 
     /// Power amplifier configuration
     pub pa_config: PaConfig,
 
+    /// Behaviour when a requested TX power is outside `TX_POWER_MIN..=TX_POWER_MAX`
+    pub power_out_of_range: OutOfRangePolicy,
+
     /// Internal packet type field to track configurations
     pub(crate) packet_type: PacketType,
 
@@ -44,7 +136,7 @@ pub struct Config {
     ///
     /// Note that in high-traffic conditions Timeout::Single must be used
     /// to avoid the radio becoming unresponsive, see the chip errata for
-    /// further details
+    /// further details and `rx_watchdog_polls` for recovery
     pub rf_timeout: Timeout,
 
     /// Crystal oscillator frequency
@@ -53,32 +145,150 @@ pub struct Config {
     /// Timeout for blocking / polling internal methods
     pub timeout_ms: u32,
 
-    /// Skip firmware version validation
+    /// Timeout awaiting busy pin de-assert in milliseconds (defaults to
+    /// `BUSY_TIMEOUT_MS`), returning `Error::BusyTimeout` on expiry.
+    ///
+    /// A timeout of zero makes all commands non-blocking, returning
+    /// `Error::BusyTimeout` immediately if the device is busy
+    pub busy_timeout_ms: u32,
+
+    /// Device reset timing, applied to the `Base` created by `Sx128x::spi`
+    /// (longer timings may be required where SDN is filtered)
+    pub reset_timing: ResetTiming,
+
+    /// Number of times to retry the busy wait prior to issuing a command on
+    /// `Error::BusyTimeout` (defaults to `NUM_RETRIES`), ignored with a zero
+    /// `busy_timeout_ms`
+    ///
+    /// Commands are never re-sent, and `SetSleep` is not retried. Register,
+    /// buffer and status reads are not retried and return `Error::BusyTimeout`
+    /// to the caller
+    pub command_retries: usize,
+
+    /// Skip firmware version validation, warning rather than returning
+    /// `Error::InvalidDevice` on an unrecognised firmware version
     pub skip_version_check: bool,
+
+    /// Firmware versions accepted on initialisation (defaults to
+    /// `FIRMWARE_VERSIONS`), this is not serialized
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub firmware_versions: &'static [u16],
+
+    /// Read and check the command status following each command write,
+    /// returning `Error::CommandFailed` on failure.
+    ///
+    /// This doubles SPI traffic so is intended only for debugging
+    pub check_command_status: bool,
+
+    /// Deliver packets received with CRC errors rather than returning
+    /// `Error::InvalidCrc`, with `PacketInfo::crc_ok` indicating the failure
+    pub deliver_crc_errors: bool,
+
+    /// Skip packet status checks on reception, delivering packets with
+    /// CRC, sync, length or abort errors flagged in `PacketInfo::packet_status`
+    /// rather than returning the matching `Error` (GFSK / FLRC / BLE only)
+    pub ignore_packet_status: bool,
+
+    /// Check for a wedged packet controller on TX / RX timeouts, returning
+    /// `Error::PacketControllerBusy` rather than `Error::Timeout` where a
+    /// device reset is required (GFSK / FLRC / BLE only).
+    ///
+    /// This adds a `GetPacketStatus` read to each timeout so is disabled by default
+    pub detect_packet_controller_busy: bool,
+
+    /// Receive watchdog interval in `check_receive` polls (disabled if `None`).
+    ///
+    /// If no receive completes (or fails) within this many polls and the radio
+    /// remains in RX, the radio is returned to standby and RX re-armed to recover
+    /// from the high-traffic lock-up described in the chip errata. This may also
+    /// re-arm an idle radio on a quiet channel, which is harmless.
+    pub rx_watchdog_polls: Option<u32>,
+
+    /// Keep the frequency synthesizer locked (FS) between ranging exchanges
+    /// rather than returning to standby, minimising and stabilising TX/RX
+    /// turnaround as timing jitter maps directly to ranging distance error.
+    ///
+    /// This increases idle current consumption and applies only in ranging mode
+    pub ranging_fs: bool,
+
+    /// Flag the device for reset and restore on repeated invalid circuit
+    /// states (disabled if `None`), see [`CircuitStateRecovery`]
+    pub circuit_state_recovery: Option<CircuitStateRecovery>,
+
+    /// Channel Activity Detection configuration (LoRa / Ranging only)
+    pub cad_params: CadParams,
+
+    /// Data buffer offset for transmitted packets, see `Sx128x::set_buffer_base_addr`
+    pub tx_base_addr: u8,
+
+    /// Data buffer offset for received packets, see `Sx128x::set_buffer_base_addr`
+    pub rx_base_addr: u8,
+
+    /// Enable the LNA high sensitivity mode, improving receive sensitivity by
+    /// ~3dB at the cost of increased receive current
+    pub lna_boost: bool,
+
+    /// Enable long preamble mode for compatibility with duty-cycled receivers,
+    /// see `Sx128x::set_long_preamble`
+    pub long_preamble: bool,
+
+    /// Return to FS rather than standby following TX / RX, reducing turnaround
+    /// latency at the cost of increased idle current, see `Sx128x::set_auto_fs`
+    pub auto_fs: bool,
+
+    /// Calibrate all blocks (`CalibrationParams::all()`) during initialisation,
+    /// following configuration of the regulator mode
+    pub calibrate_on_init: bool,
+
+    /// Skip the transition to StandbyRc on completion of initialisation,
+    /// leaving the radio in whichever state configuration left it
+    pub skip_standby: bool,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
+            version: CONFIG_VERSION,
             regulator_mode: RegulatorMode::Ldo,
             pa_config: PaConfig {
                 power: 10,
                 ramp_time: RampTime::Ramp20Us,
             },
+            power_out_of_range: OutOfRangePolicy::Clamp,
             packet_type: PacketType::None,
-            modem: Modem::LoRa(LoRaConfig::default()),
-            channel: Channel::LoRa(LoRaChannel::default()),
+            modem: Modem::default(),
+            channel: Channel::default(),
             //timeout: Timeout::Configurable{ step: TickSize::TickSize1000us, count: 1000 },
             rf_timeout: Timeout::Single,
             xtal_freq: 52000000,
             timeout_ms: 100,
+            busy_timeout_ms: BUSY_TIMEOUT_MS,
+            reset_timing: ResetTiming::default(),
+            command_retries: crate::NUM_RETRIES,
             skip_version_check: false,
+            firmware_versions: FIRMWARE_VERSIONS,
+            check_command_status: false,
+            deliver_crc_errors: false,
+            ignore_packet_status: false,
+            detect_packet_controller_busy: false,
+            rx_watchdog_polls: None,
+            ranging_fs: false,
+            circuit_state_recovery: None,
+            cad_params: CadParams::default(),
+            tx_base_addr: 0,
+            rx_base_addr: 0,
+            lna_boost: false,
+            long_preamble: false,
+            auto_fs: false,
+            calibrate_on_init: true,
+            skip_standby: false,
         }
     }
 }
 
 impl Config {
     /// Create a default FLRC configuration
+    #[cfg(feature = "modem-flrc")]
     pub fn flrc() -> Self {
         Config {
             packet_type: PacketType::Flrc,
@@ -89,6 +299,7 @@ impl Config {
     }
 
     /// Create a default GFSK configuration
+    #[cfg(feature = "modem-gfsk")]
     pub fn gfsk() -> Self {
         Config {
             packet_type: PacketType::Gfsk,
@@ -99,6 +310,7 @@ impl Config {
     }
 
     /// Create a default LoRa configuration
+    #[cfg(feature = "modem-lora")]
     pub fn lora() -> Self {
         Config {
             packet_type: PacketType::LoRa,
@@ -107,6 +319,133 @@ impl Config {
             ..Default::default()
         }
     }
+
+    /// Create a default BLE configuration
+    #[cfg(feature = "modem-ble")]
+    pub fn ble() -> Self {
+        Config {
+            packet_type: PacketType::Ble,
+            modem: Modem::Ble(BleConfig::default()),
+            channel: Channel::Ble(BleChannel::default()),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(feature = "config-toml")]
+impl Config {
+    /// Parse a configuration from TOML, filling defaults for missing fields
+    ///
+    /// Configurations from newer schema versions are rejected with
+    /// `ConfigError::Version`, older versions are upgraded to `CONFIG_VERSION`
+    pub fn from_toml(s: &str) -> Result<Self, ConfigError> {
+        let config: Config = toml::from_str(s).map_err(|_| ConfigError::Format)?;
+
+        if config.version > CONFIG_VERSION {
+            return Err(ConfigError::Version(config.version));
+        }
+
+        Ok(Config {
+            version: CONFIG_VERSION,
+            ..config
+        })
+    }
+
+    /// Serialize the configuration to TOML
+    pub fn to_toml(&self) -> Result<std::string::String, ConfigError> {
+        toml::to_string(self).map_err(|_| ConfigError::Format)
+    }
+}
+
+impl Config {
+    /// Create a `ConfigBuilder` from the default configuration
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::default()
+    }
+}
+
+/// Builder for `Config`, setting modem and channel configurations as matched
+/// pairs so mismatched modem and channel configurations are unrepresentable
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl ConfigBuilder {
+    /// Create a new builder from the default configuration
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set LoRa modem and channel configurations
+    #[cfg(feature = "modem-lora")]
+    pub fn lora(self, modem: LoRaConfig, channel: LoRaChannel) -> Self {
+        self.modem(PacketType::LoRa, Modem::LoRa(modem), Channel::LoRa(channel))
+    }
+
+    /// Set Ranging modem and channel configurations
+    #[cfg(feature = "modem-ranging")]
+    pub fn ranging(self, modem: LoRaConfig, channel: LoRaChannel) -> Self {
+        self.modem(
+            PacketType::Ranging,
+            Modem::Ranging(modem),
+            Channel::Ranging(channel),
+        )
+    }
+
+    /// Set GFSK modem and channel configurations
+    #[cfg(feature = "modem-gfsk")]
+    pub fn gfsk(self, modem: GfskConfig, channel: GfskChannel) -> Self {
+        self.modem(PacketType::Gfsk, Modem::Gfsk(modem), Channel::Gfsk(channel))
+    }
+
+    /// Set FLRC modem and channel configurations
+    #[cfg(feature = "modem-flrc")]
+    pub fn flrc(self, modem: FlrcConfig, channel: FlrcChannel) -> Self {
+        self.modem(PacketType::Flrc, Modem::Flrc(modem), Channel::Flrc(channel))
+    }
+
+    /// Set BLE modem and channel configurations
+    #[cfg(feature = "modem-ble")]
+    pub fn ble(self, modem: BleConfig, channel: BleChannel) -> Self {
+        self.modem(PacketType::Ble, Modem::Ble(modem), Channel::Ble(channel))
+    }
+
+    fn modem(mut self, packet_type: PacketType, modem: Modem, channel: Channel) -> Self {
+        self.config.packet_type = packet_type;
+        self.config.modem = modem;
+        self.config.channel = channel;
+        self
+    }
+
+    /// Set the regulator mode
+    pub fn regulator_mode(mut self, regulator_mode: RegulatorMode) -> Self {
+        self.config.regulator_mode = regulator_mode;
+        self
+    }
+
+    /// Set the power amplifier configuration
+    pub fn pa_config(mut self, pa_config: PaConfig) -> Self {
+        self.config.pa_config = pa_config;
+        self
+    }
+
+    /// Set the RF timeout for TX / RX operations
+    pub fn rf_timeout(mut self, rf_timeout: Timeout) -> Self {
+        self.config.rf_timeout = rf_timeout;
+        self
+    }
+
+    /// Set the crystal oscillator frequency
+    pub fn xtal_freq(mut self, xtal_freq: u32) -> Self {
+        self.config.xtal_freq = xtal_freq;
+        self
+    }
+
+    /// Build the configuration
+    pub fn build(self) -> Config {
+        self.config
+    }
 }
 
 impl Config {
@@ -119,6 +458,202 @@ impl Config {
     pub fn freq_to_steps(&self, f: f32) -> f32 {
         f / self.freq_step()
     }
+
+    /// Convert a provided frequency in Hz into configuration steps, returning
+    /// `None` for a zero crystal frequency or a step count exceeding 24 bits
+    pub fn freq_to_steps_checked(&self, f: u32) -> Option<u32> {
+        if self.xtal_freq == 0 {
+            return None;
+        }
+
+        let steps = self.freq_to_steps(f as f32);
+        if !(0.0..=0xFF_FFFF as f32).contains(&steps) {
+            return None;
+        }
+
+        Some(steps as u32)
+    }
+
+    /// Check the configuration without device I/O, covering modem / channel
+    /// agreement, channel frequency bounds, output power range and sync word
+    /// configuration, allowing configurations to be rejected prior to connecting
+    /// to a device
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if PacketType::from(&self.modem) != PacketType::from(&self.channel) {
+            error!(
+                "Modem {:?} does not match channel {:?}",
+                self.modem, self.channel
+            );
+            return Err(ConfigError::ModemChannelMismatch);
+        }
+
+        let (low, high) = self.channel.edges_hz();
+        if low < crate::FREQ_MIN || high > crate::FREQ_MAX {
+            error!("Channel {}-{} Hz outside supported band", low, high);
+            return Err(ConfigError::Frequency);
+        }
+
+        if self
+            .freq_to_steps_checked(self.channel.frequency())
+            .is_none()
+        {
+            error!(
+                "Channel {} Hz invalid for crystal {} Hz",
+                self.channel.frequency(),
+                self.xtal_freq
+            );
+            return Err(ConfigError::Frequency);
+        }
+
+        if self.power_out_of_range == OutOfRangePolicy::Error
+            && !(TX_POWER_MIN..=TX_POWER_MAX).contains(&self.pa_config.power)
+        {
+            error!("Power {} dBm outside supported range", self.pa_config.power);
+            return Err(ConfigError::Power);
+        }
+
+        #[cfg(feature = "modem-flrc")]
+        if let Modem::Flrc(c) = &self.modem {
+            if c.sync_word_length == flrc::FlrcSyncWordLength::None
+                && c.sync_word_match != common::SyncWordRxMatch::RADIO_RX_MATCH_SYNCWORD_OFF
+            {
+                error!("FLRC sync word matching enabled without a sync word");
+                return Err(ConfigError::SyncWordLength);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check a sync word (as provided to `Sx128x::set_syncword`) against the
+    /// configured sync word length and errata 16.4 without device I/O
+    #[cfg(any(feature = "modem-gfsk", feature = "modem-flrc", feature = "modem-ble"))]
+    pub fn validate_sync_word(&self, value: &[u8]) -> Result<(), ConfigError> {
+        let len = match &self.modem {
+            #[cfg(feature = "modem-gfsk")]
+            Modem::Gfsk(c) => (c.sync_word_length as usize >> 1) + 1,
+            #[cfg(feature = "modem-flrc")]
+            Modem::Flrc(c) => {
+                if let [0x8C, 0x32, ..] | [0x63, 0x0E, ..] = value {
+                    error!("Invalid sync word selected (see errata 16.4)");
+                    return Err(ConfigError::SyncWordErrata);
+                }
+                c.sync_word_length as usize
+            }
+            #[cfg(feature = "modem-ble")]
+            Modem::Ble(_) => 4,
+            _ => return Err(ConfigError::SyncWordLength),
+        };
+
+        if value.len() != len {
+            error!(
+                "Incorrect sync word length (actual: {}, expected: {})",
+                value.len(),
+                len
+            );
+            return Err(ConfigError::SyncWordLength);
+        }
+
+        Ok(())
+    }
+
+    /// Check the channel occupied bandwidth lies within the region's band and
+    /// the radiated output power does not exceed the region's EIRP limit.
+    ///
+    /// Radiated power is computed from the configured power (limited to the range
+    /// achievable with the configured regulator mode) plus `antenna_gain_dbi`,
+    /// returning `Error::InvalidFrequency` or `Error::InvalidPower` on failure.
+    ///
+    /// Note this is advisory only and does not replace certification
+    pub fn validate_regulatory<CommsError: Debug, PinError: Debug>(
+        &self,
+        region: Region,
+        antenna_gain_dbi: i8,
+    ) -> Result<(), crate::Error<CommsError, PinError>> {
+        let (min, max) = region.band_hz();
+
+        let (low, high) = self.channel.edges_hz();
+
+        if low < min || high > max {
+            error!(
+                "Channel {} Hz (bw: {} Hz) outside region band {}-{} Hz",
+                self.channel.frequency(),
+                self.channel.occupied_bandwidth_hz(),
+                min,
+                max
+            );
+            return Err(crate::Error::InvalidFrequency);
+        }
+
+        let power = self.pa_config.power.clamp(TX_POWER_MIN, TX_POWER_MAX);
+        let eirp = power as i16 + antenna_gain_dbi as i16;
+
+        if eirp > region.max_eirp_dbm() as i16 {
+            error!(
+                "EIRP {} dBm ({} dBm + {} dBi) exceeds region limit {} dBm",
+                eirp,
+                power,
+                antenna_gain_dbi,
+                region.max_eirp_dbm()
+            );
+            return Err(crate::Error::InvalidPower(self.pa_config.power));
+        }
+
+        Ok(())
+    }
+}
+
+/// Regulatory region presets for `Config::validate_regulatory`
+///
+/// These are minimal, advisory rule tables for the 2.4 GHz ISM band
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Region {
+    /// Europe (ETSI EN 300 328), 2400-2483.5 MHz at 20 dBm EIRP
+    Eu,
+    /// United States (FCC part 15.247), 2400-2483.5 MHz at 30 dBm conducted
+    /// with up to 6 dBi antenna gain (36 dBm EIRP)
+    Us,
+}
+
+impl Region {
+    /// Fetch the permitted band edges in Hz
+    pub fn band_hz(&self) -> (u32, u32) {
+        match self {
+            Region::Eu | Region::Us => (2_400_000_000, 2_483_500_000),
+        }
+    }
+
+    /// Fetch the maximum permitted radiated power (EIRP) in dBm
+    pub fn max_eirp_dbm(&self) -> i8 {
+        match self {
+            Region::Eu => 20,
+            Region::Us => 36,
+        }
+    }
+}
+
+/// Configuration errors from `Config::validate` and `Config::from_toml`
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConfigError {
+    /// Modem and channel configurations do not match
+    ModemChannelMismatch,
+    /// Channel (accounting for bandwidth) lies outside the supported band,
+    /// or cannot be represented with the configured crystal frequency
+    Frequency,
+    /// Output power outside the supported range with `OutOfRangePolicy::Error`
+    Power,
+    /// Sync word matching enabled without a sync word, or sync word length
+    /// does not match the configured length
+    SyncWordLength,
+    /// Sync word affected by errata 16.4 (FLRC only)
+    SyncWordErrata,
+    /// Configuration schema version newer than `CONFIG_VERSION`
+    Version(u32),
+    /// Configuration could not be parsed or serialized
+    Format,
 }
 
 /// Radio modem configuration contains fields for each modem mode
@@ -126,32 +661,98 @@ impl Config {
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Modem {
+    #[cfg(feature = "modem-gfsk")]
     Gfsk(GfskConfig),
+    #[cfg(feature = "modem-lora")]
     LoRa(LoRaConfig),
+    #[cfg(feature = "modem-flrc")]
     Flrc(FlrcConfig),
+    #[cfg(feature = "modem-ble")]
     Ble(BleConfig),
+    #[cfg(feature = "modem-ranging")]
     Ranging(LoRaConfig),
     None,
 }
 
+/// Default modem, matching the default `Channel`
+impl Default for Modem {
+    fn default() -> Self {
+        #[cfg(feature = "modem-lora")]
+        let m = Modem::LoRa(LoRaConfig::default());
+        #[cfg(all(not(feature = "modem-lora"), feature = "modem-gfsk"))]
+        let m = Modem::Gfsk(GfskConfig::default());
+        #[cfg(all(
+            not(any(feature = "modem-lora", feature = "modem-gfsk")),
+            feature = "modem-flrc"
+        ))]
+        let m = Modem::Flrc(FlrcConfig::default());
+
+        m
+    }
+}
+
+/// Maximum payload length in bytes, limited by the 256-byte data buffer and
+/// 8-bit length fields
+pub const MAX_PAYLOAD_LEN: usize = 255;
+
 impl Modem {
+    /// Fetch the maximum payload length in bytes for the modem
+    ///
+    /// For BLE this includes the 2-byte PDU header (as provided to `start_transmit`)
+    /// and depends on `BleConfig::connection_state`, FLRC is limited to
+    /// `FLRC_MAX_PAYLOAD_LEN` bytes and other modems to `MAX_PAYLOAD_LEN` bytes
+    pub fn max_payload_len(&self) -> usize {
+        match self {
+            #[cfg(feature = "modem-flrc")]
+            Modem::Flrc(_) => flrc::FLRC_MAX_PAYLOAD_LEN,
+            #[cfg(feature = "modem-ble")]
+            Modem::Ble(c) => core::cmp::min(
+                c.connection_state.max_payload_len() + ble::BLE_PDU_HEADER_LEN,
+                MAX_PAYLOAD_LEN,
+            ),
+            _ => MAX_PAYLOAD_LEN,
+        }
+    }
+
     pub fn set_payload_len(&mut self, len: u8) {
         match self {
+            #[cfg(feature = "modem-gfsk")]
             Modem::Gfsk(c) => c.payload_length = len,
+            #[cfg(feature = "modem-lora")]
             Modem::LoRa(c) => c.payload_length = len,
+            #[cfg(feature = "modem-flrc")]
             Modem::Flrc(c) => c.payload_length = len,
             _ => (),
         }
     }
+
+    /// Fetch the configured payload length (or maximum length for variable
+    /// length modes), `None` for modems without a payload length field
+    pub fn payload_len(&self) -> Option<u8> {
+        match self {
+            #[cfg(feature = "modem-gfsk")]
+            Modem::Gfsk(c) => Some(c.payload_length),
+            #[cfg(feature = "modem-lora")]
+            Modem::LoRa(c) => Some(c.payload_length),
+            #[cfg(feature = "modem-flrc")]
+            Modem::Flrc(c) => Some(c.payload_length),
+            _ => None,
+        }
+    }
 }
 
 impl From<&Modem> for PacketType {
     fn from(m: &Modem) -> Self {
         match m {
+            #[cfg(feature = "modem-gfsk")]
             Modem::Gfsk(_) => PacketType::Gfsk,
+            #[cfg(feature = "modem-lora")]
             Modem::LoRa(_) => PacketType::LoRa,
+            #[cfg(feature = "modem-ranging")]
             Modem::Ranging(_) => PacketType::LoRa,
+            #[cfg(feature = "modem-flrc")]
             Modem::Flrc(_) => PacketType::Flrc,
+            #[cfg(feature = "modem-ble")]
             Modem::Ble(_) => PacketType::Ble,
             Modem::None => PacketType::None,
         }
@@ -163,16 +764,32 @@ impl From<&Modem> for PacketType {
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Channel {
+    #[cfg(feature = "modem-gfsk")]
     Gfsk(GfskChannel),
+    #[cfg(feature = "modem-lora")]
     LoRa(LoRaChannel),
+    #[cfg(feature = "modem-flrc")]
     Flrc(FlrcChannel),
+    #[cfg(feature = "modem-ble")]
     Ble(BleChannel),
+    #[cfg(feature = "modem-ranging")]
     Ranging(LoRaChannel),
 }
 
+/// Default channel, LoRa where enabled otherwise GFSK or FLRC
 impl Default for Channel {
     fn default() -> Self {
-        Channel::LoRa(LoRaChannel::default())
+        #[cfg(feature = "modem-lora")]
+        let c = Channel::LoRa(LoRaChannel::default());
+        #[cfg(all(not(feature = "modem-lora"), feature = "modem-gfsk"))]
+        let c = Channel::Gfsk(GfskChannel::default());
+        #[cfg(all(
+            not(any(feature = "modem-lora", feature = "modem-gfsk")),
+            feature = "modem-flrc"
+        ))]
+        let c = Channel::Flrc(FlrcChannel::default());
+
+        c
     }
 }
 
@@ -182,13 +799,45 @@ impl Channel {
         use Channel::*;
 
         match self {
+            #[cfg(feature = "modem-gfsk")]
             Gfsk(c) => c.freq,
+            #[cfg(feature = "modem-lora")]
             LoRa(c) => c.freq,
+            #[cfg(feature = "modem-flrc")]
             Flrc(c) => c.freq,
+            #[cfg(feature = "modem-ble")]
             Ble(c) => c.freq,
+            #[cfg(feature = "modem-ranging")]
             Ranging(c) => c.freq,
         }
     }
+
+    /// Fetch the occupied RF bandwidth in Hz for a given modulation configuration
+    pub fn occupied_bandwidth_hz(&self) -> u32 {
+        use Channel::*;
+
+        match self {
+            #[cfg(feature = "modem-gfsk")]
+            Gfsk(c) => c.br_bw.bandwidth_hz(),
+            #[cfg(feature = "modem-lora")]
+            LoRa(c) => c.bw.get_bw_hz(),
+            #[cfg(feature = "modem-flrc")]
+            Flrc(c) => c.br_bw.bandwidth_hz(),
+            #[cfg(feature = "modem-ble")]
+            Ble(c) => c.br_bw.bandwidth_hz(),
+            #[cfg(feature = "modem-ranging")]
+            Ranging(c) => c.bw.get_bw_hz(),
+        }
+    }
+
+    /// Fetch the lower and upper channel edges in Hz (frequency ± half the
+    /// occupied bandwidth)
+    pub fn edges_hz(&self) -> (u32, u32) {
+        let f = self.frequency();
+        let half_bw = self.occupied_bandwidth_hz() / 2;
+
+        (f.saturating_sub(half_bw), f.saturating_add(half_bw))
+    }
 }
 
 impl From<&Channel> for PacketType {
@@ -196,10 +845,15 @@ impl From<&Channel> for PacketType {
         use Channel::*;
 
         match m {
+            #[cfg(feature = "modem-gfsk")]
             Gfsk(_) => PacketType::Gfsk,
+            #[cfg(feature = "modem-lora")]
             LoRa(_) => PacketType::LoRa,
+            #[cfg(feature = "modem-ranging")]
             Ranging(_) => PacketType::LoRa,
+            #[cfg(feature = "modem-flrc")]
             Flrc(_) => PacketType::Flrc,
+            #[cfg(feature = "modem-ble")]
             Ble(_) => PacketType::Ble,
         }
     }
@@ -216,9 +870,10 @@ pub enum State {
     Fs = 0x04,
     Rx = 0x05,
     Tx = 0x06,
-    #[cfg(feature = "patch-unknown-state")]
     /// Unknown state not specified in datasheet but occurs in some conditions..?
     /// See: https://github.com/rust-iot/rust-radio-sx128x/pull/76
+    ///
+    /// This is reported by `get_state` but cannot be set
     Unknown = 0x07,
 }
 
@@ -243,7 +898,6 @@ impl core::convert::TryFrom<u8> for State {
             0x04 => Ok(State::Fs),
             0x05 => Ok(State::Rx),
             0x06 => Ok(State::Tx),
-            #[cfg(feature = "patch-unknown-state")]
             0x07 => Ok(State::Unknown),
             _ => {
                 error!("Unrecognised state 0x{:x}", v);
@@ -298,19 +952,219 @@ pub struct PaConfig {
     pub ramp_time: RampTime,
 }
 
+/// Policy for handling out of range configuration values
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum OutOfRangePolicy {
+    /// Clamp the value to the supported range (with a warning)
+    Clamp,
+    /// Reject the value with `Error::InvalidPower`
+    Error,
+}
+
+/// Protocol profile bundling sync word, CRC and whitening configuration for
+/// GFSK / FLRC interoperability with other devices, see `Sx128x::apply_profile`
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ProtocolProfile<'a> {
+    /// Sync word written to sync word index 1, matching the configured sync word length
+    pub sync_word: Option<&'a [u8]>,
+    /// CRC initial value
+    pub crc_seed: Option<u16>,
+    /// CRC polynomial
+    pub crc_poly: Option<u16>,
+    /// Whitening seed, retained and re-applied following packet configuration
+    pub whitening_seed: Option<u8>,
+}
+
+/// Number of symbols used for Channel Activity Detection
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CadSymbols {
+    Symbols1 = 0x00,
+    Symbols2 = 0x20,
+    Symbols4 = 0x40,
+    Symbols8 = 0x60,
+    Symbols16 = 0x80,
+}
+
+/// Behaviour on completion of Channel Activity Detection
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CadExitMode {
+    /// Remain in standby
+    Standby,
+    /// Start receive if activity is detected, the device always returns to
+    /// standby on CAD completion so this is applied by `Sx128x::check_cad`
+    Receive,
+}
+
+/// Channel Activity Detection configuration, see `Sx128x::start_cad`
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CadParams {
+    /// Number of symbols to detect over (defaults to 4)
+    pub symbols: CadSymbols,
+    /// Behaviour on CAD completion (defaults to standby)
+    pub exit_mode: CadExitMode,
+}
+
+impl Default for CadParams {
+    fn default() -> Self {
+        Self {
+            symbols: CadSymbols::Symbols4,
+            exit_mode: CadExitMode::Standby,
+        }
+    }
+}
+
+/// Transmit status, see `Sx128x::poll_transmit`
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TransmitStatus {
+    /// Transmission in progress
+    InProgress,
+    /// Transmission complete
+    Done,
+    /// Transmission timed out
+    Timeout,
+}
+
+/// Radio event decoded from interrupt flags, see `Sx128x::poll`
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RadioEvent {
+    /// Transmission complete
+    TxDone,
+    /// Packet received
+    RxDone,
+    /// TX or RX timeout
+    Timeout,
+    /// Packet received with CRC error
+    CrcError,
+    /// Sync word error
+    SyncError,
+    /// LoRa header error
+    HeaderError,
+    /// Channel Activity Detection complete, with whether activity was detected
+    CadDone(bool),
+    /// Ranging exchange complete (result valid or response sent)
+    RangingDone,
+    /// Valid LoRa header received
+    HeaderValid,
+    /// Valid sync word received
+    SyncWordValid,
+    /// Preamble detected
+    PreambleDetected,
+}
+
+impl RadioEvent {
+    /// Decode the highest priority event from the provided interrupt flags,
+    /// completion and error events take priority over reception progress events
+    pub fn from_irq(irq: Irq) -> Option<Self> {
+        let e = if irq.contains(Irq::CRC_ERROR) {
+            RadioEvent::CrcError
+        } else if irq.contains(Irq::RX_TX_TIMEOUT) {
+            RadioEvent::Timeout
+        } else if irq.contains(Irq::RX_DONE) {
+            RadioEvent::RxDone
+        } else if irq.contains(Irq::TX_DONE) {
+            RadioEvent::TxDone
+        } else if irq.contains(Irq::CAD_DONE) {
+            RadioEvent::CadDone(irq.contains(Irq::CAD_ACTIVITY_DETECTED))
+        } else if irq
+            .intersects(Irq::RANGING_MASTER_RESULT_VALID | Irq::RANGING_SLAVE_RESPONSE_DONE)
+        {
+            RadioEvent::RangingDone
+        } else if irq.contains(Irq::RANGING_MASTER_RESULT_TIMEOUT) {
+            RadioEvent::Timeout
+        } else if irq.contains(Irq::HEADER_ERROR) {
+            RadioEvent::HeaderError
+        } else if irq.contains(Irq::SYNCWORD_ERROR) {
+            RadioEvent::SyncError
+        } else if irq.contains(Irq::HEADER_VALID) {
+            RadioEvent::HeaderValid
+        } else if irq.contains(Irq::SYNCWORD_VALID) {
+            RadioEvent::SyncWordValid
+        } else if irq.contains(Irq::PREAMBLE_DETECTED) {
+            RadioEvent::PreambleDetected
+        } else {
+            return None;
+        };
+
+        Some(e)
+    }
+}
+
+/// Receive loop control, returned by `Sx128x::receive_loop` packet handlers
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LoopControl {
+    /// Continue receiving
+    Continue,
+    /// Stop the receive loop
+    Stop,
+}
+
 /// Receive packet information
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct PacketInfo {
     pub rssi: i16,
+    /// Averaged RSSI (GFSK / FLRC / BLE only)
     pub rssi_sync: Option<i16>,
     pub snr: Option<i16>,
+    /// Raw signed SNR in quarter-dB steps (LoRa / Ranging only)
+    pub snr_raw: Option<i8>,
+    /// Packet CRC valid (or CRC disabled), only false where
+    /// `Config::deliver_crc_errors` is enabled
+    pub crc_ok: bool,
+    /// LoRa header type of the received packet, inferred from the header valid
+    /// IRQ and the configured header mode (LoRa only)
+    #[cfg(feature = "modem-lora")]
+    pub header_type: Option<lora::LoRaHeader>,
 
     pub packet_status: PacketStatus,
     pub tx_rx_status: TxRxStatus,
     pub sync_addr_status: u8,
 }
 
+impl PacketInfo {
+    /// Decode a `GetPacketStatus` response for the provided packet type
+    pub(crate) fn decode(&mut self, packet_type: PacketType, data: &[u8; 5]) {
+        self.packet_status = PacketStatus::from_bits_truncate(data[2]);
+        self.tx_rx_status = TxRxStatus::from_bits_truncate(data[3]);
+        self.sync_addr_status = data[4] & 0b0111;
+
+        match packet_type {
+            PacketType::Gfsk | PacketType::Flrc | PacketType::Ble => {
+                self.rssi_sync = Some(-(data[0] as i16) / 2);
+                self.rssi = -(data[1] as i16) / 2;
+            }
+            PacketType::LoRa | PacketType::Ranging => {
+                self.rssi = -(data[0] as i16) / 2;
+                let snr_raw = data[1] as i8;
+                self.snr_raw = Some(snr_raw);
+                self.snr = Some(snr_raw as i16 / 4);
+            }
+            PacketType::None => unimplemented!(),
+        }
+    }
+
+    /// Fetch the difference between the packet RSSI and the RSSI sampled at sync
+    /// word detection (`rssi - rssi_sync`), where available.
+    ///
+    /// A large magnitude indicates the signal level changed over the packet (for
+    /// example due to fast fading), which correlates with increased packet loss
+    pub fn rssi_fade(&self) -> Option<i16> {
+        self.rssi_sync.map(|s| self.rssi - s)
+    }
+}
+
 impl radio::ReceiveInfo for PacketInfo {
     fn rssi(&self) -> i16 {
         self.rssi
@@ -323,6 +1177,10 @@ impl Default for PacketInfo {
             rssi: -100,
             rssi_sync: None,
             snr: None,
+            snr_raw: None,
+            crc_ok: true,
+            #[cfg(feature = "modem-lora")]
+            header_type: None,
             packet_status: PacketStatus::empty(),
             tx_rx_status: TxRxStatus::empty(),
             sync_addr_status: 0,
@@ -341,6 +1199,17 @@ pub enum RegulatorMode {
     Dcdc = 0x01,
 }
 
+/// Minimum TX power in dBm
+///
+/// The PA is specified from -18 dBm to +12.5 dBm (programmed as +13 dBm) with
+/// either `RegulatorMode`, the DC/DC converter reduces current consumption rather
+/// than changing the output range. Note that at supply voltages below ~2.3 V the
+/// upper end of this range may not be achievable
+pub const TX_POWER_MIN: i8 = -18;
+
+/// Maximum TX power in dBm, see `TX_POWER_MIN`
+pub const TX_POWER_MAX: i8 = 13;
+
 /// Power amplifier ramp time
 #[derive(Copy, Clone, PartialEq, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -377,6 +1246,59 @@ pub enum PacketType {
     None = 0x0F,
 }
 
+impl core::convert::TryFrom<u8> for PacketType {
+    type Error = ();
+
+    fn try_from(v: u8) -> Result<PacketType, ()> {
+        match v {
+            0x00 => Ok(PacketType::Gfsk),
+            0x01 => Ok(PacketType::LoRa),
+            0x02 => Ok(PacketType::Ranging),
+            0x03 => Ok(PacketType::Flrc),
+            0x04 => Ok(PacketType::Ble),
+            0x0F => Ok(PacketType::None),
+            _ => {
+                error!("Unrecognised packet type {:x}", v);
+                Err(())
+            }
+        }
+    }
+}
+
+/// Receive buffer status, see `Sx128x::get_rx_buffer_status`
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RxBufferStatus {
+    /// Offset of the first byte of the received payload in the data buffer
+    pub ptr: u8,
+    /// Length of the received payload in bytes
+    pub len: u8,
+}
+
+/// Live radio status summary for diagnostics, see `Sx128x::status`
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RadioStatus {
+    /// Circuit state
+    pub state: State,
+    /// Command status
+    pub command_status: CommandStatus,
+    /// Active packet type
+    pub packet_type: PacketType,
+    /// Configured RF frequency in Hz
+    pub frequency: u32,
+}
+
+impl core::fmt::Display for RadioStatus {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{:?} ({:?}) {:?} @ {} Hz",
+            self.state, self.command_status, self.packet_type, self.frequency
+        )
+    }
+}
+
 /// Radio commands
 #[derive(Clone, PartialEq, Debug, strum::Display)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -445,8 +1367,10 @@ pub enum Registers {
     LrSyncWordBaseAddress2 = 0x09D3,
     LrSyncWordBaseAddress3 = 0x09D8,
     LrEstimatedFrequencyErrorMsb = 0x0954,
+    LrIncomingCodingRate = 0x0950,
     GfskBlePreambleLength = 0x09C1,
     LrSyncWordTolerance = 0x09CD,
+    LrLoRaSyncWordMsb = 0x0944,
     LrBleAccessAddress = 0x09CF,
     LnaRegime = 0x0891,
     EnableManuaLGainControl = 0x089F,
@@ -455,15 +1379,32 @@ pub enum Registers {
 }
 
 pub const MASK_RANGINGMUXSEL: u8 = 0xCF;
+pub const MASK_RANGING_RESULTS_FREEZE: u8 = 0x02;
 pub const MASK_LNA_REGIME: u8 = 0xC0;
 pub const MASK_MANUAL_GAIN_CONTROL: u8 = 0x80;
 pub const MASK_DEMOD_DETECTION: u8 = 0xFE;
 pub const MASK_MANUAL_GAIN_VALUE: u8 = 0xF0;
 
 pub const MASK_LR_ESTIMATED_FREQUENCY_ERROR: u32 = 0x0FFFFF;
+pub const MASK_LR_INCOMING_CODING_RATE: u8 = 0x70;
+pub const MASK_LR_INCOMING_CRC: u8 = 0x10;
+
+/// LoRa incoming header CRC info register, bit 4 (`MASK_LR_INCOMING_CRC`) is set
+/// where a received explicit header indicates a payload CRC (SX1280/1 datasheet,
+/// LoRa explicit header mode). This shares address 0x0954 with
+/// `Registers::LrEstimatedFrequencyErrorMsb`, which uses bits 3:0, so cannot be a
+/// distinct `Registers` variant.
+pub const REG_LR_INCOMING_CRC_INFO: u16 = 0x0954;
 
 pub const AUTO_RX_TX_OFFSET: u16 = 33;
 
+/// Fragment header length for fragmented transmission, see `Sx128x::transmit_fragmented`
+pub const FRAGMENT_HEADER_LEN: usize = 1;
+/// Fragment header flag marking the final fragment
+pub const FRAGMENT_LAST: u8 = 0x80;
+/// Fragment header sequence number mask
+pub const FRAGMENT_SEQ_MASK: u8 = 0x7F;
+
 #[derive(Clone, PartialEq, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -498,9 +1439,139 @@ bitflags! {
     }
 }
 
+impl Irq {
+    /// Default IRQs enabled and routed to DIO1 when starting receive
+    pub const RX_DEFAULT: Self = Self::RX_DONE
+        .union(Self::CRC_ERROR)
+        .union(Self::RX_TX_TIMEOUT)
+        .union(Self::SYNCWORD_VALID)
+        .union(Self::SYNCWORD_ERROR)
+        .union(Self::HEADER_VALID)
+        .union(Self::HEADER_ERROR)
+        .union(Self::PREAMBLE_DETECTED);
+
+    /// Default IRQs enabled and routed to DIO1 when starting transmit
+    pub const TX_DEFAULT: Self = Self::TX_DONE
+        .union(Self::CRC_ERROR)
+        .union(Self::RX_TX_TIMEOUT);
+
+    /// Interrupt flags and their descriptions, in bit order
+    const DESCRIPTIONS: [(Irq, &'static str); 16] = [
+        (Irq::TX_DONE, "TX done"),
+        (Irq::RX_DONE, "RX done"),
+        (Irq::SYNCWORD_VALID, "sync word valid"),
+        (Irq::SYNCWORD_ERROR, "sync word error"),
+        (Irq::HEADER_VALID, "header valid"),
+        (Irq::HEADER_ERROR, "header error"),
+        (Irq::CRC_ERROR, "CRC error"),
+        (
+            Irq::RANGING_SLAVE_RESPONSE_DONE,
+            "ranging slave response done",
+        ),
+        (
+            Irq::RANGING_SLAVE_REQUEST_DISCARDED,
+            "ranging slave request discarded",
+        ),
+        (
+            Irq::RANGING_MASTER_RESULT_VALID,
+            "ranging master result valid",
+        ),
+        (
+            Irq::RANGING_MASTER_RESULT_TIMEOUT,
+            "ranging master result timeout",
+        ),
+        (
+            Irq::RANGING_SLAVE_REQUEST_VALID,
+            "ranging slave request valid",
+        ),
+        (Irq::CAD_DONE, "CAD done"),
+        (Irq::CAD_ACTIVITY_DETECTED, "CAD activity detected"),
+        (Irq::RX_TX_TIMEOUT, "RX / TX timeout"),
+        (Irq::PREAMBLE_DETECTED, "preamble detected"),
+    ];
+
+    /// Describe the set interrupt flags in bit order, for logging and diagnostics
+    pub fn describe(self) -> impl Iterator<Item = &'static str> {
+        Self::DESCRIPTIONS
+            .iter()
+            .filter(move |(f, _)| self.contains(*f))
+            .map(|(_, d)| *d)
+    }
+
+    /// Fetch a wrapper rendering the set interrupt flags as a comma separated
+    /// list of descriptions, consistently via both `log` and `defmt`
+    pub fn display(self) -> IrqDisplay {
+        IrqDisplay(self)
+    }
+}
+
+/// Interrupt flag display wrapper, see `Irq::display`
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct IrqDisplay(pub Irq);
+
+impl core::fmt::Display for IrqDisplay {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "none");
+        }
+
+        for (i, d) in self.0.describe().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", d)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for IrqDisplay {
+    fn format(&self, f: defmt::Formatter) {
+        if self.0.is_empty() {
+            defmt::write!(f, "none");
+            return;
+        }
+
+        for (i, d) in self.0.describe().enumerate() {
+            if i > 0 {
+                defmt::write!(f, ", ");
+            }
+            defmt::write!(f, "{=str}", d);
+        }
+    }
+}
+
 /// DIO IRQ flag mask
 pub type DioMask = Irq;
 
+/// Fixed-size history of recent (non-empty) IRQ status reads, oldest first
+#[cfg(feature = "irq-history")]
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct IrqHistory(heapless::Vec<Irq, IRQ_HISTORY_LEN>);
+
+#[cfg(feature = "irq-history")]
+impl IrqHistory {
+    /// Append an IRQ status, discarding the oldest entry when full
+    pub fn push(&mut self, irq: Irq) {
+        if self.0.is_full() {
+            self.0.remove(0);
+        }
+        let _ = self.0.push(irq);
+    }
+
+    /// Fetch recorded IRQ statuses, oldest first
+    pub fn as_slice(&self) -> &[Irq] {
+        &self.0
+    }
+
+    /// Clear recorded IRQ statuses
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
 bitflags! {
     /// Packet status register
     #[derive(Copy, Clone, PartialEq, Debug)]
@@ -552,6 +1623,20 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// Sleep configuration, selecting the memories retained in sleep mode
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    pub struct SleepConfig: u8 {
+        /// Retain the data RAM (device configuration and saved context)
+        const RETAIN_DATA_RAM        = (1 << 0);
+        /// Retain the data buffer contents
+        const RETAIN_DATA_BUFFER     = (1 << 1);
+        /// Retain the instruction RAM
+        const RETAIN_INSTRUCTION_RAM = (1 << 2);
+    }
+}
+
 /// Ranging mode role
 #[derive(Copy, Clone, PartialEq, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -563,6 +1648,19 @@ pub enum RangingRole {
     Initiator = 0x01,
 }
 
+/// Ranging result type, selecting the `LrRangingResultConfig` result mux
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RangingResultType {
+    /// Raw result of the last exchange
+    Raw = 0x00,
+    /// Result averaged over the filter window
+    Averaged = 0x01,
+    /// Debug filtered result
+    DebugFiltered = 0x03,
+}
+
 /// TickSize for timeout calculations
 #[derive(Copy, Clone, PartialEq, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -578,6 +1676,18 @@ pub enum TickSize {
     TickSize4000us = 0x03,
 }
 
+impl TickSize {
+    /// Fetch the tick duration in nanoseconds
+    pub fn as_nanos(&self) -> u32 {
+        match self {
+            TickSize::TickSize0015us => 15_625,
+            TickSize::TickSize0062us => 62_500,
+            TickSize::TickSize1000us => 1_000_000,
+            TickSize::TickSize4000us => 4_000_000,
+        }
+    }
+}
+
 /// Timeout confguration for autonomous radio operations
 #[derive(Clone, PartialEq, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -614,4 +1724,249 @@ impl Timeout {
             Timeout::Continuous => 0xFFFF,
         }
     }
+    /// Compute the timeout duration in microseconds (step size × count),
+    /// returning `None` for `Single` and `Continuous` modes
+    pub fn as_micros(&self) -> Option<u64> {
+        match self {
+            Timeout::Configurable { step, count } => {
+                Some(step.as_nanos() as u64 * *count as u64 / 1000)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use super::{ble::*, common::*, flrc::*, gfsk::*, lora::*, *};
+    use crate::{mock::MockHal, Error, Sx128x};
+
+    #[test]
+    fn channel_occupied_bandwidth() {
+        let lora = LoRaChannel {
+            bw: LoRaBandwidth::Bw1600kHz,
+            ..Default::default()
+        };
+        assert_eq!(
+            Channel::LoRa(lora.clone()).occupied_bandwidth_hz(),
+            1_625_000
+        );
+        assert_eq!(Channel::Ranging(lora).occupied_bandwidth_hz(), 1_625_000);
+
+        let gfsk = GfskChannel::default();
+        assert_eq!(Channel::Gfsk(gfsk).occupied_bandwidth_hz(), 300_000);
+
+        let flrc = FlrcChannel::default();
+        assert_eq!(Channel::Flrc(flrc).occupied_bandwidth_hz(), 2_400_000);
+
+        let ble = BleChannel {
+            freq: 2_402_000_000,
+            br_bw: GfskBleBitrateBandwidth::BR_1_000_BW_1_2,
+            mi: GfskBleModIndex::MOD_IND_0_50,
+            ms: ModShaping::Bt0_5,
+        };
+        assert_eq!(Channel::Ble(ble).occupied_bandwidth_hz(), 1_200_000);
+    }
+
+    #[test]
+    fn bitrate_bandwidth() {
+        let br_bw = GfskBleBitrateBandwidth::BR_0_250_BW_0_3;
+        assert_eq!(br_bw.bitrate_bps(), 250_000);
+        assert_eq!(br_bw.bandwidth_hz(), 300_000);
+
+        let br_bw = FlrcBitrate::BR_1_040_BW_1_2;
+        assert_eq!(br_bw.bitrate_bps(), 1_040_000);
+        assert_eq!(br_bw.bandwidth_hz(), 1_200_000);
+    }
+
+    #[test]
+    fn validate_regulatory() {
+        let check = |c: &Config, region, gain| c.validate_regulatory::<(), ()>(region, gain);
+
+        let mut config = Config::gfsk();
+        assert_eq!(check(&config, Region::Eu, 0), Ok(()));
+
+        // 13 dBm with a 7 dBi antenna is at the EU limit, 8 dBi exceeds it
+        config.pa_config.power = 13;
+        assert_eq!(check(&config, Region::Eu, 7), Ok(()));
+        assert_eq!(check(&config, Region::Eu, 8), Err(Error::InvalidPower(13)));
+        assert_eq!(check(&config, Region::Us, 8), Ok(()));
+
+        // Power beyond the device range is limited prior to comparison
+        config.pa_config.power = 30;
+        assert_eq!(check(&config, Region::Eu, 7), Ok(()));
+
+        // 2.4 MHz occupied bandwidth overlaps the upper band edge
+        config.channel = Channel::Gfsk(GfskChannel {
+            freq: 2_483_000_000,
+            br_bw: GfskBleBitrateBandwidth::BR_2_000_BW_2_4,
+            ..Default::default()
+        });
+        assert_eq!(check(&config, Region::Us, 0), Err(Error::InvalidFrequency));
+    }
+
+    #[test]
+    fn timeout_as_micros() {
+        let t = |step, count| Timeout::Configurable { step, count }.as_micros();
+
+        assert_eq!(t(TickSize::TickSize0015us, 64), Some(1_000));
+        assert_eq!(t(TickSize::TickSize0062us, 16), Some(1_000));
+        assert_eq!(t(TickSize::TickSize1000us, 500), Some(500_000));
+        assert_eq!(t(TickSize::TickSize4000us, 0xFFFF), Some(262_140_000));
+
+        assert_eq!(Timeout::Single.as_micros(), None);
+        assert_eq!(Timeout::Continuous.as_micros(), None);
+    }
+
+    #[test]
+    fn modem_payload_len() {
+        let mut modem = Modem::Flrc(FlrcConfig::default());
+        assert_eq!(modem.payload_len(), Some(127));
+
+        modem.set_payload_len(32);
+        assert_eq!(modem.payload_len(), Some(32));
+
+        assert_eq!(Modem::None.payload_len(), None);
+
+        assert_eq!(Modem::Gfsk(GfskConfig::default()).max_payload_len(), 255);
+        assert_eq!(Modem::Flrc(FlrcConfig::default()).max_payload_len(), 127);
+
+        let ble = |connection_state| {
+            Modem::Ble(BleConfig {
+                connection_state,
+                crc_field: BleCrcFields::BLE_CRC_3B,
+                packet_type: BlePacketTypes::BLE_PRBS_9,
+                whitening: WhiteningModes::RADIO_WHITENING_ON,
+            })
+        };
+        use BleConnectionStates::*;
+        assert_eq!(ble(BLE_PAYLOAD_LENGTH_MAX_31_BYTES).max_payload_len(), 33);
+        assert_eq!(ble(BLE_PAYLOAD_LENGTH_MAX_255_BYTES).max_payload_len(), 255);
+    }
+
+    #[test]
+    fn packet_info_rssi_fade() {
+        let mut info = PacketInfo {
+            rssi: -80,
+            ..Default::default()
+        };
+        assert_eq!(info.rssi_fade(), None);
+
+        info.rssi_sync = Some(-72);
+        assert_eq!(info.rssi_fade(), Some(-8));
+    }
+
+    #[test]
+    fn config_builder() {
+        let config = Config::builder()
+            .gfsk(GfskConfig::default(), GfskChannel::default())
+            .regulator_mode(RegulatorMode::Dcdc)
+            .build();
+
+        assert_eq!(config.packet_type, PacketType::Gfsk);
+        assert_eq!(config.modem, Modem::Gfsk(GfskConfig::default()));
+        assert_eq!(config.channel, Channel::Gfsk(GfskChannel::default()));
+        assert_eq!(config.regulator_mode, RegulatorMode::Dcdc);
+
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.configure(&config).unwrap();
+    }
+
+    #[test]
+    fn packet_info_rssi_sync() {
+        let mut info = PacketInfo::default();
+        info.decode(PacketType::Gfsk, &[160, 150, 0, 0, 0]);
+        assert_eq!(info.rssi, -75);
+        assert_eq!(info.rssi_sync, Some(-80));
+
+        let mut info = PacketInfo::default();
+        info.decode(PacketType::LoRa, &[160, 0, 0, 0, 0]);
+        assert_eq!(info.rssi_sync, None);
+    }
+
+    #[test]
+    fn describe_interrupts() {
+        let irq = Irq::RX_DONE | Irq::CRC_ERROR | Irq::PREAMBLE_DETECTED;
+        assert_eq!(
+            irq.describe().collect::<Vec<_>>(),
+            vec!["RX done", "CRC error", "preamble detected"]
+        );
+        assert_eq!(
+            format!("{}", irq.display()),
+            "RX done, CRC error, preamble detected"
+        );
+        assert_eq!(format!("{}", Irq::empty().display()), "none");
+    }
+
+    #[test]
+    fn ble_default_config() {
+        let config = Config::ble();
+        assert_eq!(config.validate(), Ok(()));
+
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.configure(&config).unwrap();
+        assert_eq!(
+            radio.hal.written(&[Commands::SetPacketType as u8]),
+            vec![vec![PacketType::Ble as u8]]
+        );
+    }
+
+    #[test]
+    fn config_validate() {
+        assert_eq!(Config::default().validate(), Ok(()));
+        assert_eq!(Config::flrc().validate(), Ok(()));
+
+        let mut config = Config::gfsk();
+        config.channel = Channel::LoRa(LoRaChannel::default());
+        assert_eq!(config.validate(), Err(ConfigError::ModemChannelMismatch));
+
+        let mut config = Config::lora();
+        config.channel = Channel::LoRa(LoRaChannel {
+            freq: 2_500_000_000,
+            ..Default::default()
+        });
+        assert_eq!(config.validate(), Err(ConfigError::Frequency));
+
+        let mut config = Config::lora();
+        config.pa_config.power = 20;
+        assert_eq!(config.validate(), Ok(()));
+        config.power_out_of_range = OutOfRangePolicy::Error;
+        assert_eq!(config.validate(), Err(ConfigError::Power));
+
+        let config = Config::flrc();
+        assert_eq!(config.validate_sync_word(&[0x01, 0x02, 0x03, 0x04]), Ok(()));
+        assert_eq!(
+            config.validate_sync_word(&[0x8C, 0x32, 0x03, 0x04]),
+            Err(ConfigError::SyncWordErrata)
+        );
+        assert_eq!(
+            Config::gfsk().validate_sync_word(&[0x01, 0x02, 0x03, 0x04]),
+            Err(ConfigError::SyncWordLength)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "config-toml")]
+    fn config_toml() {
+        for config in [Config::lora(), Config::gfsk(), Config::flrc()] {
+            let s = config.to_toml().unwrap();
+            assert_eq!(Config::from_toml(&s), Ok(config));
+        }
+
+        let config = Config::from_toml("version = 1\nxtal_freq = 26000000\n").unwrap();
+        assert_eq!(config.xtal_freq, 26_000_000);
+        assert_eq!(config.pa_config, Config::default().pa_config);
+
+        assert_eq!(
+            Config::from_toml("version = 2\n"),
+            Err(ConfigError::Version(2))
+        );
+        assert_eq!(Config::from_toml("version = "), Err(ConfigError::Format));
+
+        // Unversioned configurations are treated as the legacy version
+        let config: Config = toml::from_str("xtal_freq = 26000000\n").unwrap();
+        assert_eq!(config.version, 1);
+    }
 }
@@ -0,0 +1,330 @@
+//! Command encoding for high-level operations
+//!
+//! These functions produce the SPI transactions issued by the equivalent
+//! `Sx128x` methods without requiring a radio instance, documenting the wire
+//! format and allowing users to assert on expected transactions in tests.
+
+use super::*;
+
+/// Maximum prefix length for an encoded transaction
+pub const MAX_PREFIX_LEN: usize = 3;
+
+/// Maximum data length for an encoded transaction
+pub const MAX_DATA_LEN: usize = 8;
+
+/// Maximum number of transactions produced by a single operation
+pub const MAX_TRANSACTIONS: usize = 4;
+
+/// Transaction encoding errors
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TransactionError {
+    /// Prefix or data exceeds `MAX_PREFIX_LEN` / `MAX_DATA_LEN`
+    InvalidLength,
+}
+
+/// Encoded SPI write transaction, consisting of a command prefix and data
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Transaction {
+    prefix: [u8; MAX_PREFIX_LEN],
+    prefix_len: usize,
+    data: [u8; MAX_DATA_LEN],
+    data_len: usize,
+}
+
+impl Transaction {
+    /// Create a command transaction, returning `TransactionError::InvalidLength`
+    /// where `data` exceeds `MAX_DATA_LEN`
+    pub fn command(command: Commands, data: &[u8]) -> Result<Self, TransactionError> {
+        Self::checked(&[command as u8], data)
+    }
+
+    /// Create a register write transaction, returning
+    /// `TransactionError::InvalidLength` where `data` exceeds `MAX_DATA_LEN`
+    pub fn write_regs(reg: u16, data: &[u8]) -> Result<Self, TransactionError> {
+        Self::checked(
+            &[Commands::WiteRegister as u8, (reg >> 8) as u8, reg as u8],
+            data,
+        )
+    }
+
+    fn checked(p: &[u8], d: &[u8]) -> Result<Self, TransactionError> {
+        if p.len() > MAX_PREFIX_LEN || d.len() > MAX_DATA_LEN {
+            error!(
+                "Transaction too long (prefix: {} data: {})",
+                p.len(),
+                d.len()
+            );
+            return Err(TransactionError::InvalidLength);
+        }
+
+        Ok(Self::new(p, d))
+    }
+
+    /// Encode a command with fixed-length data, the encoders in this module
+    /// never exceed `MAX_DATA_LEN` so do not require checking
+    fn cmd<const N: usize>(command: Commands, d: [u8; N]) -> Self {
+        Self::new(&[command as u8], &d)
+    }
+
+    /// Encode a register write with fixed-length data
+    #[cfg(feature = "modem-gfsk")]
+    fn regs<const N: usize>(reg: u16, d: [u8; N]) -> Self {
+        Self::new(
+            &[Commands::WiteRegister as u8, (reg >> 8) as u8, reg as u8],
+            &d,
+        )
+    }
+
+    fn new(p: &[u8], d: &[u8]) -> Self {
+        let mut prefix = [0u8; MAX_PREFIX_LEN];
+        prefix[..p.len()].copy_from_slice(p);
+
+        let mut data = [0u8; MAX_DATA_LEN];
+        data[..d.len()].copy_from_slice(d);
+
+        Self {
+            prefix,
+            prefix_len: p.len(),
+            data,
+            data_len: d.len(),
+        }
+    }
+
+    /// Fetch the command prefix (opcode and any address bytes)
+    pub fn prefix(&self) -> &[u8] {
+        &self.prefix[..self.prefix_len]
+    }
+
+    /// Fetch the command data
+    pub fn data(&self) -> &[u8] {
+        &self.data[..self.data_len]
+    }
+
+    /// Fetch the register address for register write transactions
+    pub(crate) fn reg(&self) -> Option<u16> {
+        match self.prefix() {
+            [c, msb, lsb] if *c == Commands::WiteRegister as u8 => {
+                Some((*msb as u16) << 8 | *lsb as u16)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Ordered list of transactions for a high-level operation
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Transactions {
+    items: [Option<Transaction>; MAX_TRANSACTIONS],
+    len: usize,
+}
+
+impl Transactions {
+    fn new() -> Self {
+        Self {
+            items: [None; MAX_TRANSACTIONS],
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, t: Transaction) {
+        self.items[self.len] = Some(t);
+        self.len += 1;
+    }
+
+    /// Iterate over transactions in the order they are issued
+    pub fn iter(&self) -> impl Iterator<Item = &Transaction> {
+        self.items[..self.len].iter().flatten()
+    }
+
+    /// Fetch the number of transactions
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether there are no transactions
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Encode a `SetRfFrequency` command for the provided frequency in Hz
+pub fn set_frequency(config: &Config, f: u32) -> Transaction {
+    let c = config.freq_to_steps(f as f32) as u32;
+
+    Transaction::cmd(
+        Commands::SetRfFrequency,
+        [(c >> 16) as u8, (c >> 8) as u8, c as u8],
+    )
+}
+
+/// Encode a `SetPacketType` command
+pub fn set_packet_type(packet_type: PacketType) -> Transaction {
+    Transaction::cmd(Commands::SetPacketType, [packet_type as u8])
+}
+
+/// Encode a `SetModulationParams` command for the provided channel
+pub fn set_modulation_params(ch: &Channel) -> Transaction {
+    use Channel::*;
+
+    let data = match ch {
+        #[cfg(feature = "modem-gfsk")]
+        Gfsk(c) => [c.br_bw as u8, c.mi as u8, c.ms as u8],
+        #[cfg(feature = "modem-lora")]
+        LoRa(c) => [c.sf as u8, c.bw as u8, c.cr as u8],
+        #[cfg(feature = "modem-ranging")]
+        Ranging(c) => [c.sf as u8, c.bw as u8, c.cr as u8],
+        #[cfg(feature = "modem-flrc")]
+        Flrc(c) => [c.br_bw as u8, c.cr as u8, c.ms as u8],
+        #[cfg(feature = "modem-ble")]
+        Ble(c) => [c.br_bw as u8, c.mi as u8, c.ms as u8],
+    };
+
+    Transaction::cmd(Commands::SetModulationParams, data)
+}
+
+/// Encode a `SetPacketParams` command for the provided modem
+pub fn set_packet_params(modem: &Modem) -> Transaction {
+    use Modem::*;
+
+    let data = match modem {
+        #[cfg(feature = "modem-gfsk")]
+        Gfsk(c) => [
+            c.preamble_length as u8,
+            c.sync_word_length as u8,
+            c.sync_word_match as u8,
+            c.header_type as u8,
+            c.payload_length,
+            c.crc_mode as u8,
+            c.whitening as u8,
+        ],
+        #[cfg(feature = "modem-lora")]
+        LoRa(c) => lora_packet_params(c),
+        #[cfg(feature = "modem-ranging")]
+        Ranging(c) => lora_packet_params(c),
+        #[cfg(feature = "modem-flrc")]
+        Flrc(c) => [
+            c.preamble_length as u8,
+            c.sync_word_length as u8,
+            c.sync_word_match as u8,
+            c.header_type as u8,
+            c.payload_length,
+            c.crc_mode as u8,
+            c.whitening as u8,
+        ],
+        #[cfg(feature = "modem-ble")]
+        Ble(c) => [
+            c.connection_state as u8,
+            c.crc_field as u8,
+            c.packet_type as u8,
+            c.whitening as u8,
+            0u8,
+            0u8,
+            0u8,
+        ],
+        None => [0u8; 7],
+    };
+
+    Transaction::cmd(Commands::SetPacketParams, data)
+}
+
+#[cfg(feature = "modem-lora")]
+fn lora_packet_params(c: &lora::LoRaConfig) -> [u8; 7] {
+    [
+        c.preamble_length,
+        c.header_type as u8,
+        c.payload_length,
+        c.crc_mode as u8,
+        c.invert_iq as u8,
+        0u8,
+        0u8,
+    ]
+}
+
+/// Encode the transactions issued by `Sx128x::set_channel`, given the
+/// currently active packet type
+pub fn set_channel(config: &Config, current: PacketType, ch: &Channel) -> Transactions {
+    let mut t = Transactions::new();
+
+    t.push(set_frequency(config, ch.frequency()));
+
+    let packet_type = PacketType::from(ch);
+    if current != packet_type {
+        t.push(set_packet_type(packet_type));
+    }
+
+    t.push(set_modulation_params(ch));
+
+    t
+}
+
+/// Encode the transactions issued by `Sx128x::configure_modem`, given the
+/// currently active packet type
+///
+/// Note this excludes the FLRC sync word tolerance patch and LoRa sync word,
+/// which are read-modify-writes of the existing register values
+pub fn configure_modem(current: PacketType, modem: &Modem) -> Transactions {
+    let mut t = Transactions::new();
+
+    let packet_type = PacketType::from(modem);
+    if current != packet_type {
+        t.push(set_packet_type(packet_type));
+    }
+
+    t.push(set_packet_params(modem));
+
+    #[cfg(feature = "modem-gfsk")]
+    if let Modem::Gfsk(c) = modem {
+        if c.patch_preamble {
+            t.push(Transaction::regs(
+                Registers::GfskBlePreambleLength as u16,
+                [c.preamble_length as u8],
+            ));
+        }
+    }
+
+    t
+}
+
+/// Apply the FLRC sync word patch to the current `LrSyncWordTolerance` value,
+/// forcing an exact sync word match as the default 4 bit threshold is too low
+#[cfg(feature = "modem-flrc")]
+pub(crate) fn patch_flrc_sync_word_tolerance(current: u8) -> u8 {
+    current & 0xF0
+}
+
+/// Apply a LoRa sync word to the current `LrLoRaSyncWordMsb` / LSB values, the
+/// sync word nibbles are stored in the upper nibbles of the two registers
+#[cfg(feature = "modem-lora")]
+pub(crate) fn patch_lora_sync_word(current: [u8; 2], sync_word: lora::LoRaSyncWord) -> [u8; 2] {
+    let v = sync_word.value();
+
+    [
+        (current[0] & 0x0F) | (v & 0xF0),
+        (current[1] & 0x0F) | ((v & 0x0F) << 4),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transaction_length() {
+        let t = Transaction::command(Commands::SetTx, &[1, 2, 3]).unwrap();
+        assert_eq!(t.prefix(), &[Commands::SetTx as u8]);
+        assert_eq!(t.data(), &[1, 2, 3]);
+
+        let t = Transaction::write_regs(0x0954, &[0u8; MAX_DATA_LEN]).unwrap();
+        assert_eq!(t.reg(), Some(0x0954));
+
+        assert_eq!(
+            Transaction::command(Commands::SetTx, &[0u8; MAX_DATA_LEN + 1]),
+            Err(TransactionError::InvalidLength)
+        );
+        assert_eq!(
+            Transaction::write_regs(0x0954, &[0u8; MAX_DATA_LEN + 1]),
+            Err(TransactionError::InvalidLength)
+        );
+    }
+}
@@ -9,11 +9,11 @@ use core::fmt::Debug;
 
 extern crate libc;
 
-#[cfg(any(test, feature = "util"))]
+#[cfg(any(test, feature = "util", feature = "config-toml"))]
 #[macro_use]
 extern crate std;
 
-use base::Base;
+use base::{Base, Hal as _};
 
 #[cfg(not(feature = "defmt"))]
 use log::{debug, error, trace, warn};
@@ -28,15 +28,33 @@ use embedded_hal::{
 };
 
 pub use radio::{Channel as _, Interrupts as _, State as _};
+use radio::{Receive as _, Transmit as _};
+
+#[cfg(feature = "async")]
+pub mod asynch;
+
+pub mod backoff;
 
 pub mod base;
 
 pub mod device;
+
+#[cfg(not(any(feature = "modem-lora", feature = "modem-gfsk", feature = "modem-flrc")))]
+compile_error!("At least one of the modem-lora, modem-gfsk or modem-flrc features must be enabled");
+
+#[cfg(feature = "modem-ble")]
+use device::ble::{BlePduHeader, BLE_PDU_HEADER_LEN};
 use device::*;
 pub use device::{Config, State};
 
 pub mod prelude;
 
+pub mod session;
+use session::{RxSession, TxSession};
+
+#[cfg(test)]
+mod mock;
+
 /// Sx128x Spi operating mode
 pub const SPI_MODE: SpiMode = SpiMode {
     polarity: Polarity::IdleLow,
@@ -47,18 +65,33 @@ pub const SPI_MODE: SpiMode = SpiMode {
 pub struct Sx128x<Base> {
     config: Config,
     packet_type: PacketType,
+    rx_crc_error: bool,
+    whitening_seed: Option<u8>,
+    applied_modem: Option<Modem>,
+    rx_pending: bool,
+    rx_header_valid: bool,
+    rx_idle_polls: u32,
+    invalid_states: (u32, u32),
+    recovery_pending: bool,
+    wor_active: bool,
+    beacon_interval: Option<Timeout>,
     hal: Base,
+    #[cfg(feature = "irq-history")]
+    irq_history: IrqHistory,
 }
 
 pub const FREQ_MIN: u32 = 2_400_000_000;
 pub const FREQ_MAX: u32 = 2_500_000_000;
 
+/// Default number of command retries on busy timeout, see `Config::command_retries`
 pub const NUM_RETRIES: usize = 3;
 
 /// Sx128x error type
+///
+/// Under the `defmt` feature inner communication and pin errors are rendered
+/// via their `Debug` implementations, so these need not implement `defmt::Format`
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Error<CommsError: Debug + 'static, PinError: Debug + 'static> {
     #[cfg_attr(feature = "thiserror", error("communication error: {:?}", 0))]
     /// Communications (SPI or UART) error
@@ -128,6 +161,13 @@ pub enum Error<CommsError: Debug + 'static, PinError: Debug + 'static> {
     /// Invalid configuration option provided
     InvalidConfiguration,
 
+    #[cfg_attr(
+        feature = "thiserror",
+        error("TX power out of range (requested {:?} dBm)", 0)
+    )]
+    /// Requested TX power out of range with `OutOfRangePolicy::Error`
+    InvalidPower(i8),
+
     #[cfg_attr(feature = "thiserror", error("invalid state command"))]
     /// Invalid state command
     InvalidStateCommand,
@@ -139,9 +179,70 @@ pub enum Error<CommsError: Debug + 'static, PinError: Debug + 'static> {
     /// Frequency out of range
     InvalidFrequency,
 
-    #[cfg_attr(feature = "thiserror", error("device communication failed"))]
-    /// No SPI communication detected
-    NoComms,
+    #[cfg_attr(feature = "thiserror", error("invalid packet type (received {:?})", 0))]
+    /// Radio returned an invalid packet type
+    InvalidPacketType(u8),
+
+    #[cfg_attr(
+        feature = "thiserror",
+        error("device communication failed, MISO stuck high (check wiring and SPI mode)")
+    )]
+    /// No SPI communication detected, reads returned all ones indicating MISO is
+    /// not driven (likely a wiring, chip select or SPI mode issue)
+    MisoStuckHigh,
+
+    #[cfg_attr(
+        feature = "thiserror",
+        error("device communication failed, MISO stuck low (check power and reset)")
+    )]
+    /// No SPI communication detected, reads returned all zeros indicating MISO is
+    /// held low (likely an unpowered device or one held in reset)
+    MisoStuckLow,
+
+    #[cfg_attr(
+        feature = "thiserror",
+        error("command 0x{:02x} failed (status {:?})", 0, 1)
+    )]
+    /// Radio reported a command processing or execution failure
+    CommandFailed(u8, CommandStatus),
+
+    #[cfg_attr(feature = "thiserror", error("packet controller busy"))]
+    /// Packet controller stuck busy following a timeout, device reset required
+    PacketControllerBusy,
+}
+
+#[cfg(feature = "defmt")]
+impl<CommsError: Debug + 'static, PinError: Debug + 'static> defmt::Format
+    for Error<CommsError, PinError>
+{
+    fn format(&self, f: defmt::Formatter) {
+        use Error::*;
+
+        match self {
+            Comms(e) => defmt::write!(f, "Comms({})", defmt::Debug2Format(e)),
+            Pin(e) => defmt::write!(f, "Pin({})", defmt::Debug2Format(e)),
+            Aborted => defmt::write!(f, "Aborted"),
+            Timeout => defmt::write!(f, "Timeout"),
+            BusyTimeout => defmt::write!(f, "BusyTimeout"),
+            InvalidCrc => defmt::write!(f, "InvalidCrc"),
+            InvalidLength => defmt::write!(f, "InvalidLength"),
+            InvalidSync => defmt::write!(f, "InvalidSync"),
+            Abort => defmt::write!(f, "Abort"),
+            InvalidState(a, b) => defmt::write!(f, "InvalidState({}, {})", a, b),
+            InvalidDevice(v) => defmt::write!(f, "InvalidDevice(0x{:04x})", v),
+            InvalidCircuitState(v) => defmt::write!(f, "InvalidCircuitState({})", v),
+            InvalidCommandStatus(v) => defmt::write!(f, "InvalidCommandStatus({})", v),
+            InvalidConfiguration => defmt::write!(f, "InvalidConfiguration"),
+            InvalidPower(v) => defmt::write!(f, "InvalidPower({})", v),
+            InvalidStateCommand => defmt::write!(f, "InvalidStateCommand"),
+            InvalidFrequency => defmt::write!(f, "InvalidFrequency"),
+            InvalidPacketType(v) => defmt::write!(f, "InvalidPacketType({})", v),
+            MisoStuckHigh => defmt::write!(f, "MisoStuckHigh"),
+            MisoStuckLow => defmt::write!(f, "MisoStuckLow"),
+            CommandFailed(c, s) => defmt::write!(f, "CommandFailed(0x{:02x}, {})", c, s),
+            PacketControllerBusy => defmt::write!(f, "PacketControllerBusy"),
+        }
+    }
 }
 
 pub type Sx128xSpi<Spi, BusyPin, ReadyPin, SdnPin, DelayPin> =
@@ -161,6 +262,23 @@ where
     Delay: DelayNs,
 {
     /// Create an Sx128x with the provided `Spi` implementation and pins
+    ///
+    /// Multiple radios may share an SPI bus with separate chip select lines by
+    /// providing an `SpiDevice` that arbitrates access to the bus, for example
+    /// `embedded_hal_bus::spi::RefCellDevice` (or `CriticalSectionDevice` where
+    /// radios are used from different contexts). The busy, ready and SDN pins
+    /// must _not_ be shared between radios, as resetting one radio via SDN
+    /// will corrupt any operation in progress on the other.
+    ///
+    /// ```ignore
+    /// let bus = RefCell::new(spi_bus);
+    ///
+    /// let rx_spi = RefCellDevice::new(&bus, rx_cs, Delay);
+    /// let rx = Sx128x::spi(rx_spi, rx_busy, rx_ready, rx_sdn, Delay, &config)?;
+    ///
+    /// let tx_spi = RefCellDevice::new(&bus, tx_cs, Delay);
+    /// let tx = Sx128x::spi(tx_spi, tx_busy, tx_ready, tx_sdn, Delay, &config)?;
+    /// ```
     pub fn spi(
         spi: Spi,
         busy: BusyPin,
@@ -170,18 +288,93 @@ where
         config: &Config,
     ) -> Result<Self, Error<<Spi as ErrorType>::Error, PinError>> {
         // Create SpiWrapper over spi/cs/busy
-        let hal = Base {
-            spi,
-            sdn,
-            busy,
-            ready,
-            delay,
-        };
+        let mut hal = Base::new(spi, busy, ready, sdn, delay);
+        hal.set_busy_timeout(config.busy_timeout_ms);
+        hal.set_reset_timing(config.reset_timing);
         // Create instance with new hal
         Self::new(hal, config)
     }
 }
 
+/// Decode the firmware version read from `LrFirmwareVersionMsb`, detecting
+/// a stuck MISO line where no device responds
+pub(crate) fn decode_firmware_version<CommsError: Debug, PinError: Debug>(
+    d: [u8; 2],
+) -> Result<u16, Error<CommsError, PinError>> {
+    match (d[0] as u16) << 8 | (d[1] as u16) {
+        0xFFFF => {
+            error!("No communication with device (MISO stuck high)");
+            Err(Error::MisoStuckHigh)
+        }
+        0x0000 => {
+            error!("No communication with device (MISO stuck low)");
+            Err(Error::MisoStuckLow)
+        }
+        v => Ok(v),
+    }
+}
+
+/// Check a firmware version against `Config::firmware_versions`, unless
+/// disabled with `Config::skip_version_check`
+pub(crate) fn check_firmware_version<CommsError: Debug, PinError: Debug>(
+    config: &Config,
+    firmware_version: u16,
+) -> Result<(), Error<CommsError, PinError>> {
+    if !config.firmware_versions.contains(&firmware_version) {
+        if !config.skip_version_check {
+            error!(
+                "Invalid firmware version! expected one of: {:?} actual: 0x{:x}",
+                config.firmware_versions, firmware_version
+            );
+            return Err(Error::InvalidDevice(firmware_version));
+        }
+
+        warn!(
+            "Unrecognised firmware version 0x{:x}, continuing (skip_version_check)",
+            firmware_version
+        );
+    }
+
+    Ok(())
+}
+
+/// Check the packet status of a received packet for errors unless disabled
+/// by `Config::ignore_packet_status` (GFSK / FLRC / BLE only), flagging CRC
+/// errors in `PacketInfo::crc_ok` where `Config::deliver_crc_errors` is set
+pub(crate) fn check_packet_status<CommsError: Debug, PinError: Debug>(
+    packet_type: PacketType,
+    config: &Config,
+    info: &mut PacketInfo,
+) -> Result<(), Error<CommsError, PinError>> {
+    match packet_type {
+        PacketType::Gfsk | PacketType::Flrc | PacketType::Ble if !config.ignore_packet_status => {}
+        _ => return Ok(()),
+    }
+
+    let status = info.packet_status;
+
+    if status.contains(PacketStatus::CRC_ERROR) {
+        if !config.deliver_crc_errors {
+            debug!("RX packet status CRC error");
+            return Err(Error::InvalidCrc);
+        }
+        info.crc_ok = false;
+    }
+
+    if status.contains(PacketStatus::SYNC_ERROR) {
+        debug!("RX packet status sync error");
+        Err(Error::InvalidSync)
+    } else if status.contains(PacketStatus::LENGTH_ERROR) {
+        debug!("RX packet status length error");
+        Err(Error::InvalidLength)
+    } else if status.contains(PacketStatus::ABORT_ERROR) {
+        debug!("RX packet status abort error");
+        Err(Error::Abort)
+    } else {
+        Ok(())
+    }
+}
+
 impl<Hal> Sx128x<Hal>
 where
     Hal: base::Hal,
@@ -194,91 +387,290 @@ where
         config: &Config,
     ) -> Result<Self, Error<<Hal as base::Hal>::CommsError, <Hal as base::Hal>::PinError>> {
         let mut sx128x = Self::build(hal);
+        sx128x.hal.set_busy_timeout(config.busy_timeout_ms);
 
-        debug!("Resetting device");
-
-        // Reset IC
-        sx128x.hal.reset()?;
+        // Reset IC and check communication with the radio
+        let firmware_version = Self::probe(&mut sx128x.hal)?;
+        check_firmware_version(config, firmware_version)?;
 
-        debug!("Checking firmware version");
+        debug!("Configuring device");
 
-        // Check communication with the radio
-        let firmware_version = sx128x.firmware_version()?;
+        // Configure device prior to use
+        sx128x.configure(config)?;
 
-        if firmware_version == 0xFFFF || firmware_version == 0x0000 {
-            return Err(Error::NoComms);
-        } else if firmware_version != 0xA9B5 {
-            warn!(
-                "Invalid firmware version! expected: 0x{:x} actual: 0x{:x}",
-                0xA9B5, firmware_version
-            );
+        // Calibrate following regulator mode configuration (configure leaves
+        // the device in StandbyRc)
+        if config.calibrate_on_init {
+            debug!("Calibrating device");
+            sx128x.calibrate(CalibrationParams::all())?;
         }
 
-        if firmware_version != 0xA9B5 && !config.skip_version_check {
-            // Disable version check. Known F/W version is: 0x8E8E
-            // return Err(Error::InvalidDevice(firmware_version));
+        // Ensure state is idle (unless disabled)
+        if !config.skip_standby {
+            sx128x.set_state(State::StandbyRc)?;
         }
 
-        // TODO: do we need to calibrate things here?
-        //sx128x.calibrate(CalibrationParams::default())?;
+        Ok(sx128x)
+    }
+
+    /// Reset the device and read the firmware version, checking for communication
+    /// without configuring the device
+    ///
+    /// This returns `Error::MisoStuckHigh` or `Error::MisoStuckLow` where no device
+    /// responds (or `Error::BusyTimeout` where the device does not become ready),
+    /// allowing tools to detect whether a radio is present prior to `new`
+    pub fn probe(
+        hal: &mut Hal,
+    ) -> Result<u16, Error<<Hal as base::Hal>::CommsError, <Hal as base::Hal>::PinError>> {
+        debug!("Resetting device");
 
-        debug!("Configuring device");
+        // Reset IC, waiting for the device to become ready rather than relying
+        // on reset timing alone
+        hal.reset_and_wait_ready()?;
 
-        // Configure device prior to use
-        sx128x.configure(config)?;
+        debug!("Checking firmware version");
 
-        // Ensure state is idle
-        sx128x.set_state(State::StandbyRc)?;
+        let mut d = [0u8; 2];
+        hal.read_regs(Registers::LrFirmwareVersionMsb as u16, &mut d)?;
 
-        Ok(sx128x)
+        decode_firmware_version(d)
     }
 
     pub fn reset(&mut self) -> Result<(), <Hal as base::HalError>::E> {
         debug!("Resetting device");
 
         self.hal.reset()?;
+        self.packet_type = PacketType::None;
+        self.applied_modem = None;
+
+        Ok(())
+    }
+
+    /// Reset the device and wait for the busy pin to de-assert, returning
+    /// `Error::BusyTimeout` if the device does not become ready within
+    /// `Config::busy_timeout_ms`
+    pub fn reset_and_wait_ready(&mut self) -> Result<(), <Hal as base::HalError>::E> {
+        debug!("Resetting device (awaiting ready)");
+
+        self.hal.reset_and_wait_ready()?;
+        self.packet_type = PacketType::None;
+        self.applied_modem = None;
 
         Ok(())
     }
 
+    /// Reset the device and restore the current configuration
+    pub fn reset_and_restore(&mut self) -> Result<(), <Hal as base::HalError>::E> {
+        warn!("Resetting device and restoring configuration");
+
+        self.hal.reset_and_wait_ready()?;
+        self.packet_type = PacketType::None;
+        self.applied_modem = None;
+
+        let config = self.config.clone();
+        self.configure(&config)
+    }
+
+    /// Check whether invalid circuit states have reached the
+    /// `Config::circuit_state_recovery` threshold, see `recover`
+    pub fn recovery_pending(&self) -> bool {
+        self.recovery_pending
+    }
+
+    /// Reset the device and restore the current configuration where recovery
+    /// is pending, returning whether recovery was performed.
+    ///
+    /// Recovery is not performed from within `get_status` (and the methods
+    /// using it) to avoid re-entering configuration, so this should be called
+    /// by the application following `Error::InvalidCircuitState`
+    pub fn recover(&mut self) -> Result<bool, <Hal as base::HalError>::E> {
+        if !self.recovery_pending {
+            return Ok(false);
+        }
+
+        self.recovery_pending = false;
+        self.reset_and_restore()?;
+
+        Ok(true)
+    }
+
     pub(crate) fn build(hal: Hal) -> Self {
         Sx128x {
             config: Config::default(),
             packet_type: PacketType::None,
+            rx_crc_error: false,
+            whitening_seed: None,
+            applied_modem: None,
+            rx_pending: false,
+            rx_header_valid: false,
+            rx_idle_polls: 0,
+            invalid_states: (0, 0),
+            recovery_pending: false,
+            wor_active: false,
+            beacon_interval: None,
             hal,
+            #[cfg(feature = "irq-history")]
+            irq_history: IrqHistory::default(),
         }
     }
 
     pub fn configure(&mut self, config: &Config) -> Result<(), <Hal as base::HalError>::E> {
+        self.beacon_interval = None;
+
+        // Adopt the new configuration, the active packet type remains tracked
+        // by the driver and applied values are updated by the setters below
+        let packet_type = self.config.packet_type;
+        self.config = config.clone();
+        self.config.packet_type = packet_type;
+
+        self.hal.set_busy_timeout(config.busy_timeout_ms);
+
         // Switch to standby mode
         self.set_state(State::StandbyRc)?;
 
         // Check configs match
         match (&config.modem, &config.channel) {
+            #[cfg(feature = "modem-lora")]
             (Modem::LoRa(_), Channel::LoRa(_)) => (),
+            #[cfg(feature = "modem-ranging")]
+            (Modem::Ranging(_), Channel::Ranging(_)) => (),
+            #[cfg(feature = "modem-flrc")]
             (Modem::Flrc(_), Channel::Flrc(_)) => (),
+            #[cfg(feature = "modem-gfsk")]
             (Modem::Gfsk(_), Channel::Gfsk(_)) => (),
+            #[cfg(feature = "modem-ble")]
+            (Modem::Ble(_), Channel::Ble(_)) => (),
             _ => return Err(Error::InvalidConfiguration),
         }
 
         // Update regulator mode
         self.set_regulator_mode(config.regulator_mode)?;
-        self.config.regulator_mode = config.regulator_mode;
+
+        // Update LNA sensitivity mode
+        self.set_lna_boost(config.lna_boost)?;
 
         // Update modem and channel configuration
         self.set_channel(&config.channel)?;
-        self.config.channel = config.channel.clone();
-
         self.configure_modem(&config.modem)?;
-        self.config.modem = config.modem.clone();
 
-        // Update power amplifier configuration
+        self.set_long_preamble(config.long_preamble)?;
+
+        // Return to FS rather than standby following TX / RX or ranging exchanges
+        let ranging_fs = self.idle_state() == State::Fs;
+        self.write_cmd(
+            Commands::SetAutoFs as u8,
+            &[(config.auto_fs || ranging_fs) as u8],
+        )?;
+
+        // Update power amplifier configuration (retaining any clamped power)
         self.set_power_ramp(config.pa_config.power, config.pa_config.ramp_time)?;
-        self.config.pa_config = config.pa_config.clone();
 
         Ok(())
     }
 
+    /// Fetch device circuit state and command status
+    ///
+    /// Unlike `radio::State::get_state` this returns the `CommandStatus` of the
+    /// last command, allowing detection of `ProcessingError` or `ExecutionFailure`
+    /// conditions that would otherwise go unnoticed
+    pub fn get_status(&mut self) -> Result<(State, CommandStatus), <Hal as base::HalError>::E> {
+        let mut d = [0u8; 1];
+        self.hal.read_cmd(Commands::GetStatus as u8, &mut d)?;
+
+        trace!("raw state: {}", d[0]);
+
+        let mode = (d[0] & 0b1110_0000) >> 5;
+        let m = State::try_from(mode);
+        self.track_circuit_state(m.is_ok());
+        let m = m.map_err(|_| Error::InvalidCircuitState(d[0]))?;
+
+        let status = (d[0] & 0b0001_1100) >> 2;
+        let s = CommandStatus::try_from(status).map_err(|_| Error::InvalidCommandStatus(d[0]))?;
+
+        trace!("get state: {:?} status: {:?}", m, s);
+
+        Ok((m, s))
+    }
+
+    /// Count invalid circuit states within the configured recovery window,
+    /// flagging recovery as pending once the threshold is reached
+    fn track_circuit_state(&mut self, valid: bool) {
+        let policy = match self.config.circuit_state_recovery {
+            Some(p) => p,
+            None => return,
+        };
+
+        let (errors, reads) = &mut self.invalid_states;
+
+        // Open a window on the first invalid state
+        if !valid {
+            *errors += 1;
+        }
+        if *errors == 0 {
+            return;
+        }
+        *reads += 1;
+
+        if *errors >= policy.threshold {
+            self.invalid_states = (0, 0);
+
+            warn!(
+                "Invalid circuit state threshold reached ({} in {} reads), recovery pending",
+                policy.threshold, policy.window
+            );
+            self.recovery_pending = true;
+            return;
+        }
+
+        if *reads >= policy.window {
+            self.invalid_states = (0, 0);
+        }
+    }
+
+    /// Fetch the packet type currently active on the device
+    pub fn get_packet_type(&mut self) -> Result<PacketType, <Hal as base::HalError>::E> {
+        let mut d = [0u8; 1];
+        self.hal.read_cmd(Commands::GetPacketType as u8, &mut d)?;
+
+        PacketType::try_from(d[0]).map_err(|_| Error::InvalidPacketType(d[0]))
+    }
+
+    /// Fetch the configured RF frequency in Hz
+    ///
+    /// The device does not support reading back the frequency, so this returns
+    /// the most recently configured channel frequency
+    pub fn get_frequency(&self) -> u32 {
+        self.config.channel.frequency()
+    }
+
+    /// Fetch a live status summary (state, command status, packet type and
+    /// frequency) for diagnostics, this implements `Display` for logging
+    pub fn status(&mut self) -> Result<RadioStatus, <Hal as base::HalError>::E> {
+        let (state, command_status) = self.get_status()?;
+        let packet_type = self.get_packet_type()?;
+
+        Ok(RadioStatus {
+            state,
+            command_status,
+            packet_type,
+            frequency: self.get_frequency(),
+        })
+    }
+
+    /// Fetch a live status summary as a string, see `Sx128x::status`
+    #[cfg(feature = "heapless")]
+    pub fn status_string(&mut self) -> Result<heapless::String<64>, <Hal as base::HalError>::E> {
+        use core::fmt::Write;
+
+        let status = self.status()?;
+
+        let mut s = heapless::String::new();
+        // Truncation is preferable to failing a diagnostic call
+        let _ = write!(s, "{}", status);
+
+        Ok(s)
+    }
+
     pub fn firmware_version(&mut self) -> Result<u16, <Hal as base::HalError>::E> {
         let mut d = [0u8; 2];
 
@@ -288,14 +680,155 @@ where
         Ok((d[0] as u16) << 8 | (d[1] as u16))
     }
 
+    /// Set the RF frequency in Hz, returning `Error::InvalidConfiguration` for a zero
+    /// `Config::xtal_freq` or `Error::InvalidFrequency` where the frequency exceeds
+    /// the 24-bit frequency register for the configured crystal
     pub fn set_frequency(&mut self, f: u32) -> Result<(), <Hal as base::HalError>::E> {
-        let c = self.config.freq_to_steps(f as f32) as u32;
+        if self.config.xtal_freq == 0 {
+            error!("Invalid crystal frequency (0 Hz)");
+            return Err(Error::InvalidConfiguration);
+        }
+
+        if self.config.freq_to_steps_checked(f).is_none() {
+            error!(
+                "Frequency {} Hz out of range for crystal {} Hz",
+                f, self.config.xtal_freq
+            );
+            return Err(Error::InvalidFrequency);
+        }
+
+        let t = transactions::set_frequency(&self.config, f);
+
+        trace!(
+            "Setting frequency ({:?} MHz, {:02x?})",
+            f / 1000 / 1000,
+            t.data()
+        );
+
+        self.write_transaction(&t)
+    }
+
+    /// Set the GFSK (or BLE) bitrate and bandwidth, re-issuing only the
+    /// modulation parameters for runtime rate adaptation
+    #[cfg(any(feature = "modem-gfsk", feature = "modem-ble"))]
+    pub fn set_fsk_bitrate(
+        &mut self,
+        br_bw: device::common::GfskBleBitrateBandwidth,
+    ) -> Result<(), <Hal as base::HalError>::E> {
+        let mut channel = self.config.channel.clone();
+        match &mut channel {
+            #[cfg(feature = "modem-gfsk")]
+            Channel::Gfsk(c) => c.br_bw = br_bw,
+            #[cfg(feature = "modem-ble")]
+            Channel::Ble(c) => c.br_bw = br_bw,
+            #[allow(unreachable_patterns)]
+            _ => {
+                warn!("FSK bitrate requires GFSK or BLE mode");
+                return Err(Error::InvalidConfiguration);
+            }
+        }
+
+        self.set_modulation_params(channel)
+    }
+
+    /// Set the FLRC bitrate and bandwidth, re-issuing only the modulation
+    /// parameters for runtime rate adaptation
+    #[cfg(feature = "modem-flrc")]
+    pub fn set_flrc_bitrate(
+        &mut self,
+        br_bw: device::flrc::FlrcBitrate,
+    ) -> Result<(), <Hal as base::HalError>::E> {
+        let mut channel = self.config.channel.clone();
+        match &mut channel {
+            Channel::Flrc(c) => c.br_bw = br_bw,
+            #[allow(unreachable_patterns)]
+            _ => {
+                warn!("FLRC bitrate requires FLRC mode");
+                return Err(Error::InvalidConfiguration);
+            }
+        }
+
+        self.set_modulation_params(channel)
+    }
+
+    /// Issue modulation parameters for an updated channel of the active
+    /// packet type, updating the cached channel configuration
+    #[cfg(any(feature = "modem-gfsk", feature = "modem-flrc", feature = "modem-ble"))]
+    fn set_modulation_params(
+        &mut self,
+        channel: Channel,
+    ) -> Result<(), <Hal as base::HalError>::E> {
+        if PacketType::from(&channel) != self.packet_type {
+            warn!(
+                "Channel does not match active packet type: {:?}",
+                self.packet_type
+            );
+            return Err(Error::InvalidConfiguration);
+        }
+
+        let t = transactions::set_modulation_params(&channel);
+        self.write_transaction(&t)?;
+        self.config.channel = channel;
+
+        Ok(())
+    }
+
+    /// Write a command, checking the resulting command status if
+    /// `Config::check_command_status` is enabled
+    pub(crate) fn write_cmd(
+        &mut self,
+        command: u8,
+        data: &[u8],
+    ) -> Result<(), <Hal as base::HalError>::E> {
+        let sleep = command == Commands::SetSleep as u8;
+
+        // Retries are disabled with a zero busy timeout to remain non-blocking
+        let retries = match self.config.busy_timeout_ms {
+            _ if sleep => 0,
+            0 => 0,
+            _ => self.config.command_retries,
+        };
+
+        // Only the wait for the device to become ready is retried, the command
+        // itself is never re-sent
+        trace!("write_cmd cmd: {:02x?} data: {:02x?}", command, data);
+        base::wait_busy_retries(&mut self.hal, retries)?;
+        self.hal.prefix_write(&[command], data)?;
+
+        // BUSY remains high while asleep, and reading status would wake the device
+        if sleep {
+            return Ok(());
+        }
+        self.hal.wait_busy()?;
+
+        if !self.config.check_command_status {
+            return Ok(());
+        }
+
+        let mut d = [0u8; 1];
+        self.hal.read_cmd(Commands::GetStatus as u8, &mut d)?;
 
-        trace!("Setting frequency ({:?} MHz, {} index)", f / 1000 / 1000, c);
+        let status = (d[0] & 0b0001_1100) >> 2;
+        let s = CommandStatus::try_from(status).map_err(|_| Error::InvalidCommandStatus(d[0]))?;
 
-        let data: [u8; 3] = [(c >> 16) as u8, (c >> 8) as u8, c as u8];
+        match s {
+            CommandStatus::ProcessingError | CommandStatus::ExecutionFailure => {
+                error!("Command 0x{:02x} failed (status: {:?})", command, s);
+                Err(Error::CommandFailed(command, s))
+            }
+            _ => Ok(()),
+        }
+    }
 
-        self.hal.write_cmd(Commands::SetRfFrequency as u8, &data)
+    /// Issue an encoded transaction
+    pub(crate) fn write_transaction(
+        &mut self,
+        t: &transactions::Transaction,
+    ) -> Result<(), <Hal as base::HalError>::E> {
+        match t.reg() {
+            Some(reg) => self.hal.write_regs(reg, t.data()),
+            None => self.write_cmd(t.prefix()[0], t.data()),
+        }
     }
 
     pub(crate) fn set_power_ramp(
@@ -303,13 +836,25 @@ where
         power: i8,
         ramp: RampTime,
     ) -> Result<(), <Hal as base::HalError>::E> {
-        if !(-18..=13).contains(&power) {
-            warn!("TX power out of range (-18 < p < 13)");
+        // Limit to the range supported by the PA
+        let (min, max) = (TX_POWER_MIN, TX_POWER_MAX);
+
+        if !(min..=max).contains(&power) {
+            if self.config.power_out_of_range == OutOfRangePolicy::Error {
+                error!(
+                    "TX power {} dBm out of range ({} <= p <= {})",
+                    power, min, max
+                );
+                return Err(Error::InvalidPower(power));
+            }
+
+            warn!(
+                "TX power {} dBm out of range, clamping ({} <= p <= {})",
+                power, min, max
+            );
         }
 
-        // Limit to -18 to +13 dBm
-        let power = core::cmp::max(power, -18);
-        let power = core::cmp::min(power, 13);
+        let power = power.clamp(min, max);
         let power_reg = (power + 18) as u8;
 
         trace!(
@@ -322,8 +867,7 @@ where
         self.config.pa_config.power = power;
         self.config.pa_config.ramp_time = ramp;
 
-        self.hal
-            .write_cmd(Commands::SetTxParams as u8, &[power_reg, ramp as u8])
+        self.write_cmd(Commands::SetTxParams as u8, &[power_reg, ramp as u8])
     }
 
     /// Set IRQ mask
@@ -331,12 +875,71 @@ where
         trace!("Setting IRQ mask: {:?}", irq);
 
         let raw = irq.bits();
-        self.hal.write_cmd(
+        self.write_cmd(
             Commands::SetDioIrqParams as u8,
             &[(raw >> 8) as u8, (raw & 0xff) as u8],
         )
     }
 
+    /// Clear only the specified interrupts, leaving any others pending
+    pub fn clear_interrupts(&mut self, mask: Irq) -> Result<(), <Hal as base::HalError>::E> {
+        trace!("Clearing IRQs: {}", mask.display());
+
+        let raw = mask.bits();
+        self.write_cmd(
+            Commands::ClearIrqStatus as u8,
+            &[(raw >> 8) as u8, (raw & 0xff) as u8],
+        )
+    }
+
+    /// Fetch current interrupts, clearing only those pending in `clear` so that
+    /// other flags remain pending for separate handling
+    pub fn get_interrupts_masked(&mut self, clear: Irq) -> Result<Irq, <Hal as base::HalError>::E> {
+        let mut data = [0u8; 2];
+
+        self.hal.read_cmd(Commands::GetIrqStatus as u8, &mut data)?;
+        let irq = Irq::from_bits((data[0] as u16) << 8 | data[1] as u16).unwrap();
+
+        let pending = irq & clear;
+        if !pending.is_empty() {
+            self.clear_interrupts(pending)?;
+        }
+
+        // Track unread packets to avoid re-reading stale buffer contents
+        if irq.contains(Irq::RX_DONE) {
+            self.rx_pending = true;
+        }
+
+        // Track CRC state for delivery of errored packets
+        if irq.intersects(Irq::RX_DONE | Irq::CRC_ERROR) {
+            self.rx_crc_error = irq.contains(Irq::CRC_ERROR);
+        }
+
+        // Latch header reception, as this may be cleared prior to RX_DONE
+        if irq.contains(Irq::HEADER_VALID) {
+            self.rx_header_valid = true;
+        }
+
+        if !irq.is_empty() {
+            trace!("irq: {}", irq.display());
+
+            #[cfg(feature = "irq-history")]
+            self.irq_history.push(irq);
+        }
+
+        Ok(irq)
+    }
+
+    /// Reset IRQ and DIO masks to the driver defaults, undoing any custom routing
+    ///
+    /// This enables the union of the IRQs used by `start_transmit`
+    /// ([`Irq::TX_DEFAULT`]) and `start_receive` ([`Irq::RX_DEFAULT`]),
+    /// routing these to DIO1 with DIO2 and DIO3 disabled.
+    pub fn reset_irq_masks(&mut self) -> Result<(), <Hal as base::HalError>::E> {
+        let irqs = Irq::TX_DEFAULT | Irq::RX_DEFAULT;
+        self.set_irq_dio_mask(irqs, irqs, DioMask::empty(), DioMask::empty())
+    }
+
     /// Set the IRQ and DIO masks
     pub fn set_irq_dio_mask(
         &mut self,
@@ -369,88 +972,76 @@ where
             (raw_dio3 & 0xff) as u8,
         ];
 
-        self.hal.write_cmd(Commands::SetDioIrqParams as u8, &data)
+        self.write_cmd(Commands::SetDioIrqParams as u8, &data)
+    }
+
+    /// Switch the device packet type, issuing `SetPacketType` only where this
+    /// differs from the active packet type
+    pub(crate) fn ensure_packet_type(
+        &mut self,
+        packet_type: PacketType,
+    ) -> Result<(), <Hal as base::HalError>::E> {
+        if self.packet_type != packet_type {
+            debug!("Setting packet type: {:?}", packet_type);
+
+            self.write_transaction(&transactions::set_packet_type(packet_type))?;
+            self.packet_type = packet_type;
+        }
+
+        Ok(())
     }
 
     pub(crate) fn configure_modem(
         &mut self,
         config: &Modem,
     ) -> Result<(), <Hal as base::HalError>::E> {
-        use Modem::*;
-
         debug!("Setting modem config: {:?}", config);
+        self.applied_modem = None;
 
-        // First update packet type (if required)
-        let packet_type = PacketType::from(config);
-        if self.packet_type != packet_type {
-            trace!("Setting packet type: {:?}", packet_type);
-            self.hal
-                .write_cmd(Commands::SetPacketType as u8, &[packet_type as u8])?;
-            self.packet_type = packet_type;
+        // Update packet type (if required) and packet configuration
+        self.ensure_packet_type(PacketType::from(config))?;
+        for t in transactions::configure_modem(self.packet_type, config).iter() {
+            self.write_transaction(t)?;
         }
 
-        let data = match config {
-            Gfsk(c) => [
-                c.preamble_length as u8,
-                c.sync_word_length as u8,
-                c.sync_word_match as u8,
-                c.header_type as u8,
-                c.payload_length,
-                c.crc_mode as u8,
-                c.whitening as u8,
-            ],
-            LoRa(c) | Ranging(c) => [
-                c.preamble_length,
-                c.header_type as u8,
-                c.payload_length,
-                c.crc_mode as u8,
-                c.invert_iq as u8,
-                0u8,
-                0u8,
-            ],
-            Flrc(c) => [
-                c.preamble_length as u8,
-                c.sync_word_length as u8,
-                c.sync_word_match as u8,
-                c.header_type as u8,
-                c.payload_length,
-                c.crc_mode as u8,
-                c.whitening as u8,
-            ],
-            Ble(c) => [
-                c.connection_state as u8,
-                c.crc_field as u8,
-                c.packet_type as u8,
-                c.whitening as u8,
-                0u8,
-                0u8,
-                0u8,
-            ],
-            None => [0u8; 7],
-        };
-
-        self.hal.write_cmd(Commands::SetPacketParams as u8, &data)?;
+        // Packet configuration resets the whitening seed, re-apply if set
+        if let Some(seed) = self.whitening_seed {
+            self.hal
+                .write_reg(Registers::LrWhitSeedBaseAddr as u16, seed)?;
+        }
 
         // Apply patches
         match config {
-            Flrc(c) if c.patch_syncword => {
+            #[cfg(feature = "modem-flrc")]
+            Modem::Flrc(c) if c.patch_syncword => {
                 // Apply sync-word patch for FLRC mode
                 self.patch_flrc_syncword()?;
             }
-            Gfsk(c) if c.patch_preamble => {
-                // Write preamble length for GFSK mode
-                self.hal.write_reg(
-                    Registers::GfskBlePreambleLength as u16,
-                    c.preamble_length as u8,
-                )?;
+            #[cfg(feature = "modem-lora")]
+            Modem::LoRa(c) => {
+                if let Some(sync_word) = c.sync_word {
+                    self.set_lora_sync_word(sync_word)?;
+                }
             }
+            // GFSK preamble length patch is included in the modem transactions
             _ => (),
         }
 
+        self.applied_modem = Some(config.clone());
+
         Ok(())
     }
 
-    pub(crate) fn get_rx_buffer_status(&mut self) -> Result<(u8, u8), <Hal as base::HalError>::E> {
+    /// Fetch the receive buffer pointer and length of the last received packet
+    ///
+    /// In LoRa implicit header mode the length is read from the payload length
+    /// register as the buffer status reports zero, in BLE mode the length
+    /// includes the 2-byte PDU header that the buffer status excludes.
+    ///
+    /// This allows reading the data buffer directly without `get_received`,
+    /// note this does not check or clear the pending reception
+    pub fn get_rx_buffer_status(&mut self) -> Result<RxBufferStatus, <Hal as base::HalError>::E> {
+        #[cfg(feature = "modem-lora")]
         use device::lora::LoRaHeader;
 
         let mut status = [0u8; 2];
@@ -459,12 +1050,14 @@ where
             .read_cmd(Commands::GetRxBufferStatus as u8, &mut status)?;
 
         let len = match &self.config.modem {
+            #[cfg(feature = "modem-lora")]
             Modem::LoRa(c) => match c.header_type {
                 LoRaHeader::Implicit => self.hal.read_reg(Registers::LrPayloadLength as u16)?,
                 LoRaHeader::Explicit => status[0],
             },
             // BLE status[0] does not include 2-byte PDU header
-            Modem::Ble(_) => status[0] + 2,
+            #[cfg(feature = "modem-ble")]
+            Modem::Ble(_) => status[0].saturating_add(2),
             _ => status[0],
         };
 
@@ -472,7 +1065,31 @@ where
 
         trace!("RX buffer ptr: {} len: {}", rx_buff_ptr, len);
 
-        Ok((rx_buff_ptr, len))
+        Ok(RxBufferStatus {
+            ptr: rx_buff_ptr,
+            len,
+        })
+    }
+
+    /// Read a sub-range of the data buffer starting `offset` bytes from `ptr`,
+    /// allowing received payloads to be streamed in chunks smaller than the payload
+    /// in combination with `get_rx_buffer_status`
+    ///
+    /// The 256-byte data buffer is circular, so reads extending past the end of the
+    /// buffer wrap around to the start. Reads longer than the buffer return
+    /// `Error::InvalidLength`, and as with `get_rx_buffer_status` this does not
+    /// check or clear the pending reception
+    pub fn read_buffer_chunked(
+        &mut self,
+        ptr: u8,
+        out: &mut [u8],
+        offset: u8,
+    ) -> Result<(), <Hal as base::HalError>::E> {
+        if out.len() > 256 {
+            return Err(Error::InvalidLength);
+        }
+
+        self.hal.read_buff(ptr.wrapping_add(offset), out)
     }
 
     pub(crate) fn get_packet_info(
@@ -483,25 +1100,16 @@ where
         self.hal
             .read_cmd(Commands::GetPacketStatus as u8, &mut data)?;
 
-        info.packet_status = PacketStatus::from_bits_truncate(data[2]);
-        info.tx_rx_status = TxRxStatus::from_bits_truncate(data[3]);
-        info.sync_addr_status = data[4] & 0b0111;
+        info.decode(self.packet_type, &data);
 
-        match self.packet_type {
-            PacketType::Gfsk | PacketType::Flrc | PacketType::Ble => {
-                info.rssi = -(data[1] as i16) / 2;
-                let rssi_avg = -(data[0] as i16) / 2;
-                trace!("Raw RSSI: {}", info.rssi);
-                trace!("Average RSSI: {}", rssi_avg);
-            }
-            PacketType::LoRa | PacketType::Ranging => {
-                info.rssi = -(data[0] as i16) / 2;
-                info.snr = Some(match data[1] < 128 {
-                    true => data[1] as i16 / 4,
-                    false => (data[1] as i16 - 256) / 4,
-                });
-            }
-            PacketType::None => unimplemented!(),
+        // Implicit header packets never raise the header valid IRQ
+        #[cfg(feature = "modem-lora")]
+        if self.packet_type == PacketType::LoRa {
+            info.header_type = match &self.config.modem {
+                _ if self.rx_header_valid => Some(lora::LoRaHeader::Explicit),
+                Modem::LoRa(c) => Some(c.header_type),
+                _ => None,
+            };
         }
 
         debug!("Info: {:?}", info);
@@ -509,9 +1117,57 @@ where
         Ok(())
     }
 
+    /// Save the current device context and enter sleep mode with the provided
+    /// retention configuration
+    ///
+    /// Where `SleepConfig::RETAIN_DATA_RAM` is set the saved context is restored on
+    /// wake, so `wake` resumes without the need to `configure` the device again
+    pub fn sleep_retain(&mut self, config: SleepConfig) -> Result<(), <Hal as base::HalError>::E> {
+        debug!("Saving context and entering sleep (config: {:?})", config);
+
+        self.write_cmd(Commands::SetSaveContext as u8, &[])?;
+        self.sleep(config)
+    }
+
+    /// Enter sleep mode with the provided retention configuration
+    ///
+    /// `radio::State::set_state(State::Sleep)` retains nothing. Retaining the data
+    /// RAM (configuration) allows the device to resume in `StandbyRc` on `wake`
+    /// without reconfiguration, at the cost of increased sleep current
+    pub fn sleep(&mut self, config: SleepConfig) -> Result<(), <Hal as base::HalError>::E> {
+        trace!("Entering sleep (config: {:?})", config);
+
+        // Configuration is lost unless the data RAM is retained
+        if !config.contains(SleepConfig::RETAIN_DATA_RAM) {
+            self.packet_type = PacketType::None;
+            self.applied_modem = None;
+        }
+
+        self.write_cmd(Commands::SetSleep as u8, &[config.bits()])
+    }
+
+    /// Wake the device from sleep, returning to `StandbyRc`
+    ///
+    /// The device is woken by a `GetStatus` transaction as the busy pin remains
+    /// asserted during sleep. If configuration was not retained `configure` must
+    /// be called prior to further operations
+    pub fn wake(&mut self) -> Result<(), <Hal as base::HalError>::E> {
+        debug!("Waking from sleep");
+
+        self.hal
+            .prefix_write(&[Commands::GetStatus as u8, 0], &[])?;
+        self.hal.wait_busy()?;
+
+        self.set_state(State::StandbyRc)
+    }
+
+    /// Calibrate the provided blocks
+    ///
+    /// Calibration must be performed in `StandbyRc`, and should be repeated
+    /// after changing the regulator mode
     pub fn calibrate(&mut self, c: CalibrationParams) -> Result<(), <Hal as base::HalError>::E> {
         trace!("Calibrate {:?}", c);
-        self.hal.write_cmd(Commands::Calibrate as u8, &[c.bits()])
+        self.write_cmd(Commands::Calibrate as u8, &[c.bits()])
     }
 
     pub(crate) fn set_regulator_mode(
@@ -519,21 +1175,121 @@ where
         r: RegulatorMode,
     ) -> Result<(), <Hal as base::HalError>::E> {
         trace!("Set regulator mode {:?}", r);
+        self.write_cmd(Commands::SetRegulatorMode as u8, &[r as u8])
+    }
+
+    /// Enable or disable the LNA high sensitivity (boost) mode
+    ///
+    /// This improves receive sensitivity by ~3dB in exchange for increased
+    /// receive current, and is retained in `Config::lna_boost` to be re-applied
+    /// on subsequent configuration
+    pub fn set_lna_boost(&mut self, enable: bool) -> Result<(), <Hal as base::HalError>::E> {
+        debug!("Set LNA boost: {}", enable);
+
+        let value = if enable { MASK_LNA_REGIME } else { 0 };
         self.hal
-            .write_cmd(Commands::SetRegulatorMode as u8, &[r as u8])
+            .update_reg(Registers::LnaRegime as u16, MASK_LNA_REGIME, value)?;
+        self.config.lna_boost = enable;
+
+        Ok(())
+    }
+
+    /// Enable or disable long preamble mode
+    ///
+    /// This allows compatibility with duty-cycled receivers that wake periodically
+    /// and only affects GFSK and FLRC packet types. The setting is retained in
+    /// `Config::long_preamble` to be re-applied on subsequent configuration
+    pub fn set_long_preamble(&mut self, enable: bool) -> Result<(), <Hal as base::HalError>::E> {
+        debug!("Set long preamble: {}", enable);
+
+        self.write_cmd(Commands::SetLongPreamble as u8, &[enable as u8])?;
+        self.config.long_preamble = enable;
+        self.wor_active = false;
+
+        Ok(())
+    }
+
+    /// Enable or disable automatic FS, returning to the FS state rather than
+    /// standby following TX or RX
+    ///
+    /// Keeping the synthesizer running reduces TX / RX turnaround latency (for
+    /// example in ACK protocols) at the cost of increased idle current. The
+    /// setting is retained in `Config::auto_fs` to be re-applied on subsequent
+    /// configuration
+    pub fn set_auto_fs(&mut self, enable: bool) -> Result<(), <Hal as base::HalError>::E> {
+        debug!("Set auto FS: {}", enable);
+
+        self.write_cmd(Commands::SetAutoFs as u8, &[enable as u8])?;
+        self.config.auto_fs = enable;
+
+        Ok(())
+    }
+
+    /// Set a fixed receiver gain (`Some(gain)`, masked to 4 bits), disabling
+    /// automatic gain control, or restore automatic gain control (`None`)
+    ///
+    /// This is intended for controlled RSSI testing, manual gain control is
+    /// enabled and demodulator detection disabled prior to writing the gain value
+    pub fn set_manual_gain(&mut self, gain: Option<u8>) -> Result<(), <Hal as base::HalError>::E> {
+        debug!("Set manual gain: {:?}", gain);
+
+        match gain {
+            Some(g) => {
+                self.hal.update_reg(
+                    Registers::EnableManuaLGainControl as u16,
+                    MASK_MANUAL_GAIN_CONTROL,
+                    MASK_MANUAL_GAIN_CONTROL,
+                )?;
+                self.hal
+                    .update_reg(Registers::DemodDetection as u16, !MASK_DEMOD_DETECTION, 0)?;
+                self.hal.update_reg(
+                    Registers::ManualGainValue as u16,
+                    !MASK_MANUAL_GAIN_VALUE,
+                    g,
+                )?;
+            }
+            None => {
+                self.hal.update_reg(
+                    Registers::EnableManuaLGainControl as u16,
+                    MASK_MANUAL_GAIN_CONTROL,
+                    0,
+                )?;
+                self.hal.update_reg(
+                    Registers::DemodDetection as u16,
+                    !MASK_DEMOD_DETECTION,
+                    !MASK_DEMOD_DETECTION,
+                )?;
+            }
+        }
+
+        Ok(())
     }
 
-    // TODO: this could got into a mode config object maybe?
-    #[allow(dead_code)]
-    pub(crate) fn set_auto_tx(&mut self, a: AutoTx) -> Result<(), <Hal as base::HalError>::E> {
+    /// Configure automatic transmission following reception, for ACK / poll
+    /// protocols requiring a programmable RX to TX turnaround
+    ///
+    /// The timeout must exceed `AUTO_RX_TX_OFFSET` (the internal turnaround
+    /// time), returning `Error::InvalidConfiguration` otherwise
+    pub fn set_auto_tx(&mut self, a: AutoTx) -> Result<(), <Hal as base::HalError>::E> {
         let data = match a {
             AutoTx::Enabled(timeout_us) => {
-                let compensated = timeout_us - AUTO_RX_TX_OFFSET;
+                // A compensated timeout of zero is not representable (disables AutoTx)
+                let compensated = match timeout_us.checked_sub(AUTO_RX_TX_OFFSET) {
+                    Some(c) if c > 0 => c,
+                    _ => {
+                        warn!(
+                            "AutoTx timeout {} us below minimum ({} us)",
+                            timeout_us,
+                            AUTO_RX_TX_OFFSET + 1
+                        );
+                        return Err(Error::InvalidConfiguration);
+                    }
+                };
                 [(compensated >> 8) as u8, (compensated & 0xff) as u8]
             }
             AutoTx::Disabled => [0u8; 2],
         };
-        self.hal.write_cmd(Commands::SetAutoTx as u8, &data)
+        self.write_cmd(Commands::SetAutoTx as u8, &data)
     }
 
     pub(crate) fn set_buff_base_addr(
@@ -542,12 +1298,31 @@ where
         rx: u8,
     ) -> Result<(), <Hal as base::HalError>::E> {
         trace!("Set buff base address (tx: {}, rx: {})", tx, rx);
-        self.hal
-            .write_cmd(Commands::SetBufferBaseAddress as u8, &[tx, rx])
+        self.write_cmd(Commands::SetBufferBaseAddress as u8, &[tx, rx])
+    }
+
+    /// Set the data buffer offsets for transmitted and received packets, applied
+    /// on each subsequent transmit and receive
+    ///
+    /// Separating these (for example TX at 0x00 and RX at 0x80) allows a packet
+    /// to be received without overwriting a pending outgoing packet, note that
+    /// packets extending past the end of the 256-byte buffer wrap around
+    pub fn set_buffer_base_addr(
+        &mut self,
+        tx: u8,
+        rx: u8,
+    ) -> Result<(), <Hal as base::HalError>::E> {
+        self.set_buff_base_addr(tx, rx)?;
+
+        self.config.tx_base_addr = tx;
+        self.config.rx_base_addr = rx;
+
+        Ok(())
     }
 
     /// Set the sychronization mode for a given index (1-3).
-    /// This is 5-bytes for GFSK mode and 4-bytes for FLRC and BLE modes.
+    /// This is 5-bytes for GFSK mode and 4-bytes for FLRC and BLE modes, or the
+    /// configured `sync_word_length` for GFSK and FLRC modems.
     pub fn set_syncword(
         &mut self,
         index: u8,
@@ -561,32 +1336,13 @@ where
 
         // Check sync words for errata 16.4
         if self.packet_type == PacketType::Flrc {
-            match &value[0..2] {
-                &[0x8C, 0x32] | &[0x63, 0x0E] => {
-                    error!("Invalid sync word selected (see errata 16.4)");
-                    return Err(Error::InvalidConfiguration);
-                }
-                _ => (),
+            if let [0x8C, 0x32, ..] | [0x63, 0x0E, ..] = value {
+                error!("Invalid sync word selected (see errata 16.4)");
+                return Err(Error::InvalidConfiguration);
             }
         }
 
-        // Calculate sync word base address and expected length
-        let (addr, len) = match (&self.packet_type, index) {
-            (PacketType::Gfsk, 1) => (Registers::LrSyncWordBaseAddress1 as u16, 5),
-            (PacketType::Gfsk, 2) => (Registers::LrSyncWordBaseAddress2 as u16, 5),
-            (PacketType::Gfsk, 3) => (Registers::LrSyncWordBaseAddress3 as u16, 5),
-            (PacketType::Flrc, 1) => (Registers::LrSyncWordBaseAddress1 as u16 + 1, 4),
-            (PacketType::Flrc, 2) => (Registers::LrSyncWordBaseAddress2 as u16 + 1, 4),
-            (PacketType::Flrc, 3) => (Registers::LrSyncWordBaseAddress3 as u16 + 1, 4),
-            (PacketType::Ble, _) => (Registers::LrSyncWordBaseAddress1 as u16 + 1, 4),
-            _ => {
-                warn!(
-                    "Invalid sync word configuration (mode: {:?} index: {} value: {:?}",
-                    self.config.modem, index, value
-                );
-                return Err(Error::InvalidConfiguration);
-            }
-        };
+        let (addr, len) = self.syncword_addr(index)?;
 
         // Check length is correct
         if value.len() != len {
@@ -605,304 +1361,455 @@ where
         Ok(())
     }
 
-    /// Apply patch for sync-word match errata in FLRC mode
-    fn patch_flrc_syncword(&mut self) -> Result<(), <Hal as base::HalError>::E> {
-        // If we're in FLRC mode, patch to force 100% match on syncwords
-        // because otherwise the 4 bit threshold is too low
-        if let PacketType::Flrc = &self.packet_type {
-            let r = self.hal.read_reg(Registers::LrSyncWordTolerance as u16)?;
-            self.hal
-                .write_reg(Registers::LrSyncWordTolerance as u16, r & 0xF0)?;
-        }
+    /// Fetch the register address and length of the sync word at the provided
+    /// index for the active packet type and configured sync word length
+    fn syncword_addr(&self, index: u8) -> Result<(u16, usize), <Hal as base::HalError>::E> {
+        // Calculate sync word base address and expected length
+        let (addr, len) = match (&self.packet_type, index) {
+            (PacketType::Gfsk, 1) => (Registers::LrSyncWordBaseAddress1 as u16, 5),
+            (PacketType::Gfsk, 2) => (Registers::LrSyncWordBaseAddress2 as u16, 5),
+            (PacketType::Gfsk, 3) => (Registers::LrSyncWordBaseAddress3 as u16, 5),
+            (PacketType::Flrc, 1) => (Registers::LrSyncWordBaseAddress1 as u16 + 1, 4),
+            (PacketType::Flrc, 2) => (Registers::LrSyncWordBaseAddress2 as u16 + 1, 4),
+            (PacketType::Flrc, 3) => (Registers::LrSyncWordBaseAddress3 as u16 + 1, 4),
+            (PacketType::Ble, _) => (Registers::LrSyncWordBaseAddress1 as u16 + 1, 4),
+            _ => {
+                warn!(
+                    "Invalid sync word configuration (mode: {:?} index: {})",
+                    self.config.modem, index
+                );
+                return Err(Error::InvalidConfiguration);
+            }
+        };
 
-        Ok(())
-    }
-}
+        // Cross-check against the configured sync word length, shorter sync
+        // words occupy the last bytes of the sync word register
+        let configured = match &self.config.modem {
+            #[cfg(feature = "modem-gfsk")]
+            Modem::Gfsk(c) if self.packet_type == PacketType::Gfsk => {
+                (c.sync_word_length as usize >> 1) + 1
+            }
+            #[cfg(feature = "modem-flrc")]
+            Modem::Flrc(c) if self.packet_type == PacketType::Flrc => c.sync_word_length as usize,
+            _ => len,
+        };
 
-impl<Hal> DelayNs for Sx128x<Hal>
-where
-    Hal: base::Hal,
-{
-    fn delay_ns(&mut self, t: u32) {
-        self.hal.delay_ns(t);
+        Ok((addr + (len - configured) as u16, configured))
     }
-}
 
-/// `radio::State` implementation for the SX128x
-impl<Hal> radio::State for Sx128x<Hal>
-where
-    Hal: base::Hal,
-{
-    type State = State;
-    type Error = <Hal as base::HalError>::E;
+    /// Set the LoRa sync word, using a public / private network preset or a
+    /// custom value
+    #[cfg(feature = "modem-lora")]
+    pub fn set_lora_sync_word(
+        &mut self,
+        sync_word: device::lora::LoRaSyncWord,
+    ) -> Result<(), <Hal as base::HalError>::E> {
+        if self.packet_type != PacketType::LoRa {
+            warn!("LoRa sync word requires LoRa mode");
+            return Err(Error::InvalidConfiguration);
+        }
 
-    /// Fetch device state
-    fn get_state(&mut self) -> Result<Self::State, Self::Error> {
-        let mut d = [0u8; 1];
-        self.hal.read_cmd(Commands::GetStatus as u8, &mut d)?;
+        let v = sync_word.value();
+        debug!("Setting LoRa sync word: {:?} (0x{:02x})", sync_word, v);
 
-        trace!("raw state: {}", d[0]);
+        let mut d = [0u8; 2];
+        self.hal
+            .read_regs(Registers::LrLoRaSyncWordMsb as u16, &mut d)?;
 
-        let mode = (d[0] & 0b1110_0000) >> 5;
-        let m = State::try_from(mode).map_err(|_| Error::InvalidCircuitState(d[0]))?;
+        let d = transactions::patch_lora_sync_word(d, sync_word);
+        self.hal.write_regs(Registers::LrLoRaSyncWordMsb as u16, &d)
+    }
 
-        let status = (d[0] & 0b0001_1100) >> 2;
-        let s = CommandStatus::try_from(status).map_err(|_| Error::InvalidCommandStatus(d[0]))?;
+    /// Set the BLE access address, required to communicate on a specific
+    /// advertising or connection channel (`0x8E89BED6` for advertising)
+    ///
+    /// The access address is written MSB first to `LrBleAccessAddress`
+    pub fn set_ble_access_address(&mut self, addr: u32) -> Result<(), <Hal as base::HalError>::E> {
+        if self.packet_type != PacketType::Ble {
+            warn!("BLE access address requires BLE mode");
+            return Err(Error::InvalidConfiguration);
+        }
 
-        trace!("get state: {:?} status: {:?}", m, s);
+        debug!("Setting BLE access address: 0x{:08x}", addr);
 
-        Ok(m)
+        self.hal
+            .write_regs(Registers::LrBleAccessAddress as u16, &addr.to_be_bytes())
     }
 
-    /// Set device state
-    fn set_state(&mut self, state: Self::State) -> Result<(), Self::Error> {
-        let command = match state {
-            State::Tx => Commands::SetTx,
-            State::Rx => Commands::SetRx,
-            //State::Cad => Commands::SetCad,
-            State::Fs => Commands::SetFs,
-            State::StandbyRc | State::StandbyXosc => Commands::SetStandby,
-            State::Sleep => Commands::SetSleep,
-            #[cfg(feature = "patch-unknown-state")]
-            State::Unknown => return Err(Error::InvalidStateCommand),
+    /// Configure the FLRC sync word (index 1) and receive match mode in one call.
+    ///
+    /// This updates the packet parameters with the match mode, writes the sync
+    /// word and applies the sync word tolerance patch (errata 16.4), in that order.
+    /// Sync words forbidden by the errata return `Error::InvalidConfiguration`.
+    #[cfg(feature = "modem-flrc")]
+    pub fn configure_flrc_sync(
+        &mut self,
+        word: &[u8; 4],
+        match_mode: device::common::SyncWordRxMatch,
+    ) -> Result<(), <Hal as base::HalError>::E> {
+        let mut modem = match &self.config.modem {
+            Modem::Flrc(c) => c.clone(),
+            _ => {
+                warn!("FLRC sync configuration requires FLRC mode");
+                return Err(Error::InvalidConfiguration);
+            }
         };
+        modem.sync_word_match = match_mode;
+        let modem = Modem::Flrc(modem);
 
-        trace!("Setting state {:?} ({})", state, command);
+        // Check errata before applying any configuration
+        if let [0x8C, 0x32, ..] | [0x63, 0x0E, ..] = word {
+            error!("Invalid sync word selected (see errata 16.4)");
+            return Err(Error::InvalidConfiguration);
+        }
 
-        self.hal.write_cmd(command as u8, &[0u8])
-    }
-}
+        self.configure_modem(&modem)?;
+        self.config.modem = modem;
 
-/// `radio::Busy` implementation for the SX128x
-impl<Hal> radio::Busy for Sx128x<Hal>
-where
-    Hal: base::Hal,
-{
-    type Error = <Hal as base::HalError>::E;
+        self.set_syncword(1, word)?;
+        self.patch_flrc_syncword()
+    }
 
-    /// Fetch device state
-    fn is_busy(&mut self) -> Result<bool, Self::Error> {
-        let irq = self.get_interrupts(false)?;
+    /// Apply a protocol profile (sync word, CRC seed / polynomial and whitening
+    /// seed) for the current GFSK or FLRC configuration.
+    ///
+    /// As packet configuration (`SetPacketParams`) resets the whitening seed, this
+    /// is written last and retained to be re-applied on subsequent modem configuration.
+    pub fn apply_profile(
+        &mut self,
+        profile: &ProtocolProfile,
+    ) -> Result<(), <Hal as base::HalError>::E> {
+        debug!("Applying protocol profile: {:?}", profile);
 
-        if irq.contains(Irq::SYNCWORD_VALID)
-            && !(irq.contains(Irq::RX_DONE) || irq.contains(Irq::CRC_ERROR))
-        {
-            return Ok(true);
+        match self.packet_type {
+            PacketType::Gfsk | PacketType::Flrc => (),
+            _ => {
+                warn!(
+                    "Protocol profile unsupported for mode: {:?}",
+                    self.packet_type
+                );
+                return Err(Error::InvalidConfiguration);
+            }
         }
 
-        Ok(false)
-    }
-}
-
-/// `radio::Channel` implementation for the SX128x
-impl<Hal> radio::Channel for Sx128x<Hal>
-where
-    Hal: base::Hal,
-{
-    /// Channel consists of an operating frequency and packet mode
-    type Channel = Channel;
-
-    type Error = <Hal as base::HalError>::E;
+        if let Some(sync_word) = profile.sync_word {
+            self.set_syncword(1, sync_word)?;
+        }
 
-    /// Set operating channel
-    fn set_channel(&mut self, ch: &Self::Channel) -> Result<(), Self::Error> {
-        use Channel::*;
+        if let Some(seed) = profile.crc_seed {
+            self.hal.write_regs(
+                Registers::LrCrcSeedBaseAddr as u16,
+                &[(seed >> 8) as u8, seed as u8],
+            )?;
+        }
 
-        debug!("Setting channel config: {:?}", ch);
+        if let Some(poly) = profile.crc_poly {
+            self.hal.write_regs(
+                Registers::LrCrcPolyBaseAddr as u16,
+                &[(poly >> 8) as u8, poly as u8],
+            )?;
+        }
 
-        // Set frequency
-        let freq = ch.frequency();
-        if !(FREQ_MIN..=FREQ_MAX).contains(&freq) {
-            return Err(Error::InvalidFrequency);
+        self.whitening_seed = profile.whitening_seed;
+        if let Some(seed) = profile.whitening_seed {
+            self.hal
+                .write_reg(Registers::LrWhitSeedBaseAddr as u16, seed)?;
         }
 
-        self.set_frequency(freq)?;
+        Ok(())
+    }
 
-        // First update packet type (if required)
-        let packet_type = PacketType::from(ch);
-        if self.packet_type != packet_type {
-            self.hal
-                .write_cmd(Commands::SetPacketType as u8, &[packet_type as u8])?;
-            self.packet_type = packet_type;
+    /// Read back the protocol profile applied to the device (sync word 1, CRC
+    /// seed / polynomial and whitening seed) for the current GFSK or FLRC
+    /// configuration, for example to verify `apply_profile`.
+    ///
+    /// The sync word is read into `sync_word`, which must be at least the
+    /// configured sync word length
+    pub fn read_profile<'a>(
+        &mut self,
+        sync_word: &'a mut [u8],
+    ) -> Result<ProtocolProfile<'a>, <Hal as base::HalError>::E> {
+        match self.packet_type {
+            PacketType::Gfsk | PacketType::Flrc => (),
+            _ => return Err(Error::InvalidConfiguration),
         }
 
-        // Then write modulation configuration
-        let data = match ch {
-            Gfsk(c) => [c.br_bw as u8, c.mi as u8, c.ms as u8],
-            LoRa(c) | Ranging(c) => [c.sf as u8, c.bw as u8, c.cr as u8],
-            Flrc(c) => [c.br_bw as u8, c.cr as u8, c.ms as u8],
-            Ble(c) => [c.br_bw as u8, c.mi as u8, c.ms as u8],
-        };
+        let (addr, len) = self.syncword_addr(1)?;
+        let sync_word = sync_word.get_mut(..len).ok_or(Error::InvalidLength)?;
+        self.hal.read_regs(addr, sync_word)?;
 
+        let mut crc_seed = [0u8; 2];
         self.hal
-            .write_cmd(Commands::SetModulationParams as u8, &data)
-    }
-}
+            .read_regs(Registers::LrCrcSeedBaseAddr as u16, &mut crc_seed)?;
 
-/// `radio::Power` implementation for the SX128x
-impl<Hal> radio::Power for Sx128x<Hal>
-where
-    Hal: base::Hal,
-{
-    type Error = <Hal as base::HalError>::E;
+        let mut crc_poly = [0u8; 2];
+        self.hal
+            .read_regs(Registers::LrCrcPolyBaseAddr as u16, &mut crc_poly)?;
 
-    /// Set TX power in dBm
-    fn set_power(&mut self, power: i8) -> Result<(), <Hal as base::HalError>::E> {
-        let ramp_time = self.config.pa_config.ramp_time;
-        self.set_power_ramp(power, ramp_time)
+        let whitening_seed = self.hal.read_reg(Registers::LrWhitSeedBaseAddr as u16)?;
+
+        let sync_word: &'a [u8] = sync_word;
+        Ok(ProtocolProfile {
+            sync_word: Some(sync_word),
+            crc_seed: Some(u16::from_be_bytes(crc_seed)),
+            crc_poly: Some(u16::from_be_bytes(crc_poly)),
+            whitening_seed: Some(whitening_seed),
+        })
     }
-}
 
-/// `radio::Interrupts` implementation for the SX128x
-impl<Hal> radio::Interrupts for Sx128x<Hal>
-where
-    Hal: base::Hal,
-{
-    type Irq = Irq;
-    type Error = <Hal as base::HalError>::E;
+    /// Set the data whitening LFSR seed for GFSK, FLRC or BLE modes
+    ///
+    /// The seed is only used where whitening is enabled in the modem configuration
+    /// (`whitening: WhiteningModes::RADIO_WHITENING_ON`), and must match that of the
+    /// peer device. As with `apply_profile` this is retained and re-applied on
+    /// subsequent modem configuration.
+    pub fn set_whitening_seed(&mut self, seed: u8) -> Result<(), <Hal as base::HalError>::E> {
+        debug!("Setting whitening seed: 0x{:02x}", seed);
 
-    /// Fetch (and optionally clear) current interrupts
-    fn get_interrupts(&mut self, clear: bool) -> Result<Self::Irq, Self::Error> {
-        let mut data = [0u8; 2];
+        match self.packet_type {
+            PacketType::Gfsk | PacketType::Flrc | PacketType::Ble => (),
+            _ => {
+                warn!(
+                    "Whitening seed unsupported for mode: {:?}",
+                    self.packet_type
+                );
+                return Err(Error::InvalidConfiguration);
+            }
+        }
 
-        self.hal.read_cmd(Commands::GetIrqStatus as u8, &mut data)?;
-        let irq = Irq::from_bits((data[0] as u16) << 8 | data[1] as u16).unwrap();
+        self.whitening_seed = Some(seed);
+        self.hal
+            .write_reg(Registers::LrWhitSeedBaseAddr as u16, seed)
+    }
 
-        if clear && !irq.is_empty() {
-            self.hal.write_cmd(Commands::ClearIrqStatus as u8, &data)?;
-        }
+    /// Set the preamble length in bits (GFSK / FLRC only)
+    ///
+    /// This is a hardware limit, the GFSK / FLRC packet parameters only encode 4
+    /// to 32 bits in steps of 4 bits and other lengths return
+    /// `Error::InvalidConfiguration`. For longer preambles (for example to wake
+    /// duty-cycled receivers) see `set_long_preamble`.
+    ///
+    /// The radio is switched to standby (aborting any TX / RX) as packet parameters
+    /// can only be applied in standby, then the modem configuration is updated
+    /// and the packet parameters re-applied, including the GFSK preamble register
+    /// where `GfskConfig::patch_preamble` is set
+    #[cfg(any(feature = "modem-gfsk", feature = "modem-flrc"))]
+    pub fn set_preamble_length(&mut self, bits: u16) -> Result<(), <Hal as base::HalError>::E> {
+        debug!("Setting preamble length: {} bits", bits);
+
+        let len = match common::PreambleLength::from_bits(bits) {
+            Some(v) => v,
+            None => {
+                warn!("Unsupported preamble length: {} bits", bits);
+                return Err(Error::InvalidConfiguration);
+            }
+        };
 
-        if !irq.is_empty() {
-            trace!("irq: {:?}", irq);
+        let mut modem = self.config.modem.clone();
+        match &mut modem {
+            #[cfg(feature = "modem-gfsk")]
+            Modem::Gfsk(c) => c.preamble_length = len,
+            #[cfg(feature = "modem-flrc")]
+            Modem::Flrc(c) => c.preamble_length = len,
+            _ => {
+                warn!("Preamble length unsupported for modem: {:?}", modem);
+                return Err(Error::InvalidConfiguration);
+            }
         }
 
-        Ok(irq)
+        self.set_state(State::StandbyRc)?;
+        self.configure_modem(&modem)?;
+        self.config.modem = modem;
+
+        Ok(())
     }
-}
 
-/// `radio::Transmit` implementation for the SX128x
-impl<Hal> radio::Transmit for Sx128x<Hal>
-where
-    Hal: base::Hal,
-{
-    type Error = <Hal as base::HalError>::E;
+    /// Read a single device register
+    ///
+    /// This is intended for debugging and experimentation, writes via
+    /// `write_register` may conflict with driver-managed configuration
+    pub fn read_register(&mut self, reg: Registers) -> Result<u8, <Hal as base::HalError>::E> {
+        self.hal.read_reg(reg as u16)
+    }
 
-    /// Start transmitting a packet
-    fn start_transmit(&mut self, data: &[u8]) -> Result<(), Self::Error> {
-        debug!("TX start");
+    /// Write a single device register
+    pub fn write_register(
+        &mut self,
+        reg: Registers,
+        val: u8,
+    ) -> Result<(), <Hal as base::HalError>::E> {
+        debug!("Writing register {} (0x{:02x})", reg, val);
 
-        // Set state to idle before we write configuration
-        self.set_state(State::StandbyRc)?;
+        self.hal.write_reg(reg as u16, val)
+    }
 
-        let s = self.get_state()?;
-        debug!("TX setup state: {:?}", s);
+    /// Fetch recent IRQ status reads (oldest first) for post-failure diagnostics
+    ///
+    /// Only non-empty reads are recorded, so idle polling does not flush the history
+    #[cfg(feature = "irq-history")]
+    pub fn irq_history(&self) -> &[Irq] {
+        self.irq_history.as_slice()
+    }
 
-        // Set packet mode
-        let mut modem_config = self.config.modem.clone();
-        modem_config.set_payload_len(data.len() as u8);
+    /// Clear the recorded IRQ history
+    #[cfg(feature = "irq-history")]
+    pub fn clear_irq_history(&mut self) {
+        self.irq_history.clear();
+    }
 
-        if let Err(e) = self.configure_modem(&modem_config) {
-            if let Ok(s) = self.get_state() {
-                error!("TX error setting modem (state: {:?})", s);
-            } else {
-                error!("TX error setting modem",);
-            }
-            return Err(e);
+    /// Check whether the packet controller is busy (GFSK / FLRC / BLE modes).
+    ///
+    /// Following a timeout this indicates the packet controller has wedged (for example
+    /// after a malformed FLRC reception) and will not recover without a device reset,
+    /// see `reset_and_restore`.
+    pub fn packet_controller_busy(&mut self) -> Result<bool, <Hal as base::HalError>::E> {
+        if let PacketType::LoRa | PacketType::Ranging | PacketType::None = self.packet_type {
+            return Ok(false);
         }
 
-        // Reset buffer addr
-        if let Err(e) = self.set_buff_base_addr(0, 0) {
-            if let Ok(s) = self.get_state() {
-                error!("TX error setting buffer base addr (state: {:?})", s);
-            } else {
-                error!("TX error setting buffer base addr",);
-            }
+        let mut data = [0u8; 5];
+        self.hal
+            .read_cmd(Commands::GetPacketStatus as u8, &mut data)?;
 
-            return Err(e);
+        let busy =
+            PacketStatus::from_bits_truncate(data[2]).contains(PacketStatus::PACKET_CONTROLER_BUSY);
+        if busy {
+            error!("Packet controller busy");
         }
 
-        // Write data to be sent
-        debug!("TX data: {:?}", data);
-        self.hal.write_buff(0, data)?;
+        Ok(busy)
+    }
 
-        // Configure ranging if used
-        if PacketType::Ranging == self.packet_type {
-            self.hal.write_cmd(
-                Commands::SetRangingRole as u8,
-                &[RangingRole::Initiator as u8],
-            )?;
+    /// Check for a wedged packet controller following a timeout, only where
+    /// enabled by `Config::detect_packet_controller_busy`
+    fn check_packet_controller(&mut self) -> Result<bool, <Hal as base::HalError>::E> {
+        match self.config.detect_packet_controller_busy {
+            true => self.packet_controller_busy(),
+            false => Ok(false),
         }
+    }
 
-        // Setup timout
-        let config = [
-            self.config.rf_timeout.step() as u8,
-            ((self.config.rf_timeout.count() >> 8) & 0x00FF) as u8,
-            (self.config.rf_timeout.count() & 0x00FF) as u8,
-        ];
+    /// Poll for the current channel RSSI without checking the radio is in
+    /// receive mode, where readings outside of RX are not meaningful
+    pub fn poll_rssi_unchecked(&mut self) -> Result<i16, <Hal as base::HalError>::E> {
+        let mut raw = [0u8; 1];
+        self.hal.read_cmd(Commands::GetRssiInst as u8, &mut raw)?;
+        Ok(-(raw[0] as i16) / 2)
+    }
 
-        // Enable IRQs
-        let irqs = Irq::TX_DONE | Irq::CRC_ERROR | Irq::RX_TX_TIMEOUT;
-        self.set_irq_dio_mask(irqs, irqs, DioMask::empty(), DioMask::empty())?;
+    /// Fetch the available RSSI samples for the most recently received packet,
+    /// returning the number of samples written.
+    ///
+    /// The SX128x does not expose a per-byte RSSI / AGC trace, so this provides the
+    /// samples the chip does report: the packet RSSI (sampled at sync for GFSK / FLRC / BLE
+    /// and averaged over the packet for LoRa) followed by the current instantaneous RSSI.
+    pub fn rssi_trace(&mut self, out: &mut [i16]) -> Result<usize, <Hal as base::HalError>::E> {
+        let mut info = PacketInfo::default();
+        self.get_packet_info(&mut info)?;
 
-        // Enter transmit mode
-        self.hal.write_cmd(Commands::SetTx as u8, &config)?;
+        let samples = [info.rssi, self.poll_rssi_unchecked()?];
+        let n = core::cmp::min(out.len(), samples.len());
+        out[..n].copy_from_slice(&samples[..n]);
 
-        trace!("TX start issued");
+        Ok(n)
+    }
+
+    /// Start radio in receive mode with the provided RF timeout
+    pub(crate) fn start_receive_timeout(
+        &mut self,
+        timeout: &Timeout,
+    ) -> Result<(), <Hal as base::HalError>::E> {
+        debug!("RX start (timeout: {:?})", timeout);
+
+        self.prepare_receive()?;
+
+        // Enter receive mode
+        self.set_rx(timeout)?;
 
         let state = self.get_state()?;
-        trace!("State: {:?}", state);
+
+        debug!("RX started (state: {:?})", state);
 
         Ok(())
     }
 
-    /// Check for transmit completion
-    fn check_transmit(&mut self) -> Result<bool, Self::Error> {
-        // Poll on DIO and short-circuit if not asserted
-        #[cfg(feature = "poll_irq")]
-        if self.hal.get_dio()? == PinState::Low {
-            return Ok(false);
+    /// Fetch the idle state used while configuring the radio for TX / RX,
+    /// this is FS for ranging with `Config::ranging_fs` and StandbyRc otherwise
+    fn idle_state(&self) -> State {
+        match self.is_ranging() && self.config.ranging_fs {
+            true => State::Fs,
+            false => State::StandbyRc,
         }
+    }
 
-        let irq = self.get_interrupts(true)?;
-        let state = self.get_state()?;
+    /// Check whether the radio is configured for ranging, this uses the modem
+    /// configuration as ranging modems configure the LoRa packet type
+    pub(crate) fn is_ranging(&self) -> bool {
+        #[cfg(feature = "modem-ranging")]
+        if let Modem::Ranging(_) = self.config.modem {
+            return true;
+        }
 
-        trace!("TX poll (irq: {:?}, state: {:?})", irq, state);
+        false
+    }
 
-        if irq.contains(Irq::TX_DONE) {
-            debug!("TX complete");
-            Ok(true)
-        } else if irq.contains(Irq::RX_TX_TIMEOUT) {
-            debug!("TX timeout");
-            Err(Error::Timeout)
-        } else {
-            Ok(false)
+    /// Read the decoded LoRa header of a packet under reception, allowing
+    /// receivers to size buffers or abort oversized packets before the
+    /// payload has been received.
+    ///
+    /// Returns `None` until `Irq::HEADER_VALID` has been raised, and always
+    /// in implicit header or non-LoRa modes where no header is transmitted
+    #[cfg(feature = "modem-lora")]
+    pub fn peek_header(
+        &mut self,
+    ) -> Result<Option<device::lora::HeaderInfo>, <Hal as base::HalError>::E> {
+        if self.packet_type != PacketType::LoRa {
+            return Ok(None);
         }
-    }
-}
 
-/// `radio::Receive` implementation for the SX128x
-impl<Hal> radio::Receive for Sx128x<Hal>
-where
-    Hal: base::Hal,
-{
-    /// Receive info structure
-    type Info = PacketInfo;
+        // Latch header reception without clearing IRQs
+        self.get_interrupts(false)?;
+        if !self.rx_header_valid {
+            return Ok(None);
+        }
 
-    /// RF Error object
-    type Error = <Hal as base::HalError>::E;
+        let mut status = [0u8; 2];
+        self.hal
+            .read_cmd(Commands::GetRxBufferStatus as u8, &mut status)?;
 
-    /// Start radio in receive mode
-    fn start_receive(&mut self) -> Result<(), Self::Error> {
-        debug!("RX start");
+        let cr = self.hal.read_reg(Registers::LrIncomingCodingRate as u16)?;
+        let crc = self.hal.read_reg(REG_LR_INCOMING_CRC_INFO)?;
+
+        let header = device::lora::HeaderInfo {
+            length: status[0],
+            coding_rate: device::lora::LoRaCodingRate::try_from(
+                (cr & MASK_LR_INCOMING_CODING_RATE) >> 4,
+            )
+            .ok(),
+            crc: crc & MASK_LR_INCOMING_CRC != 0,
+        };
+
+        debug!("RX header: {:?}", header);
+
+        Ok(Some(header))
+    }
+
+    /// Configure buffer, modem and IRQs for receive prior to entering RX
+    fn prepare_receive(&mut self) -> Result<(), <Hal as base::HalError>::E> {
+        // Received data overwrites any preloaded beacon
+        self.beacon_interval = None;
+        self.rx_header_valid = false;
 
         // Set state to idle before we write configuration
-        self.set_state(State::StandbyRc)?;
+        self.set_state(self.idle_state())?;
+        self.end_wor()?;
 
         let s = self.get_state()?;
         debug!("RX setup state: {:?}", s);
 
         // Reset buffer addr
-        if let Err(e) = self.set_buff_base_addr(0, 0) {
+        if let Err(e) = self.set_buff_base_addr(self.config.tx_base_addr, self.config.rx_base_addr)
+        {
             if let Ok(s) = self.get_state() {
                 error!("RX error setting buffer base addr (state: {:?})", s);
             } else {
@@ -911,144 +1818,3268 @@ where
             return Err(e);
         }
 
-        // Set packet mode
-        // TODO: surely this should not bre required _every_ receive?
-        let modem_config = self.config.modem.clone();
+        // Set packet mode, only where this differs from the applied configuration
+        // (for example following transmission with an adjusted payload length)
+        if self.applied_modem.as_ref() != Some(&self.config.modem) {
+            let modem_config = self.config.modem.clone();
 
-        if let Err(e) = self.configure_modem(&modem_config) {
-            if let Ok(s) = self.get_state() {
-                error!("RX error setting configuration (state: {:?})", s);
-            } else {
-                error!("RX error setting configuration",);
+            if let Err(e) = self.configure_modem(&modem_config) {
+                if let Ok(s) = self.get_state() {
+                    error!("RX error setting configuration (state: {:?})", s);
+                } else {
+                    error!("RX error setting configuration",);
+                }
+                return Err(e);
             }
-            return Err(e);
         }
 
         // Configure ranging if used
         if PacketType::Ranging == self.packet_type {
-            self.hal.write_cmd(
+            self.write_cmd(
                 Commands::SetRangingRole as u8,
                 &[RangingRole::Responder as u8],
             )?;
         }
 
-        // Setup timout
-        let config = [
-            self.config.rf_timeout.step() as u8,
-            ((self.config.rf_timeout.count() >> 8) & 0x00FF) as u8,
-            (self.config.rf_timeout.count() & 0x00FF) as u8,
-        ];
-
         // Enable IRQs
-        let irqs = Irq::RX_DONE
-            | Irq::CRC_ERROR
-            | Irq::RX_TX_TIMEOUT
-            | Irq::SYNCWORD_VALID
-            | Irq::SYNCWORD_ERROR
-            | Irq::HEADER_VALID
-            | Irq::HEADER_ERROR
-            | Irq::PREAMBLE_DETECTED;
+        let irqs = Irq::RX_DEFAULT;
+        self.set_irq_dio_mask(irqs, irqs, DioMask::empty(), DioMask::empty())
+    }
 
-        self.set_irq_dio_mask(irqs, irqs, DioMask::empty(), DioMask::empty())?;
+    /// Start wake-on-radio receive, duty cycling between `sniff` windows in RX
+    /// and `sleep` windows in sleep with long preamble detection enabled, so a
+    /// detected preamble extends the RX window until the packet is received.
+    ///
+    /// `sniff` and `sleep` must be configurable timeouts with the same step size,
+    /// and transmitters must use a preamble spanning `sniff + sleep` to be reliably
+    /// detected. `tx_preamble_symbols` is the preamble length used by transmitters
+    /// and is advisory only: it is not written to the device, and is used to log a
+    /// warning where the preamble is too short (LoRa only).
+    ///
+    /// Long preamble mode is restored to `Config::long_preamble` on starting a
+    /// subsequent receive or transmit.
+    pub fn start_wor(
+        &mut self,
+        sniff: Timeout,
+        sleep: Timeout,
+        tx_preamble_symbols: u16,
+    ) -> Result<(), <Hal as base::HalError>::E> {
+        let (sniff_us, sleep_us) = match (sniff.as_micros(), sleep.as_micros()) {
+            (Some(a), Some(b)) if sniff.step() == sleep.step() => (a, b),
+            _ => {
+                warn!("WOR requires configurable sniff / sleep timeouts with matching steps");
+                return Err(Error::InvalidConfiguration);
+            }
+        };
 
-        // Enter transmit mode
-        self.hal.write_cmd(Commands::SetRx as u8, &config)?;
+        // Check the preamble spans a full sniff / sleep cycle
+        #[cfg(feature = "modem-lora")]
+        #[allow(irrefutable_let_patterns)]
+        if let Channel::LoRa(c) = &self.config.channel {
+            let sf = (c.sf as u8 >> 4) as u64;
+            let symbol_us = (1u64 << sf) * 1_000_000 / c.bw.get_bw_hz() as u64;
+            let preamble_us = symbol_us * tx_preamble_symbols as u64;
 
-        let state = self.get_state()?;
+            if preamble_us < sniff_us + sleep_us {
+                warn!(
+                    "WOR preamble ({} us) shorter than sniff + sleep ({} us), packets may be missed",
+                    preamble_us,
+                    sniff_us + sleep_us
+                );
+            }
+        }
 
-        debug!("RX started (state: {:?})", state);
+        debug!(
+            "WOR start (sniff: {} us, sleep: {} us, preamble: {} symbols)",
+            sniff_us, sleep_us, tx_preamble_symbols
+        );
 
-        Ok(())
+        self.prepare_receive()?;
+
+        // Long preamble is enabled only for the duration of WOR, leaving
+        // `Config::long_preamble` to be restored by `end_wor`
+        self.write_cmd(Commands::SetLongPreamble as u8, &[1])?;
+        self.wor_active = true;
+
+        self.set_rx_duty_cycle(&sniff, &sleep)
     }
 
-    /// Check for a received packet
-    fn check_receive(&mut self, restart: bool) -> Result<bool, Self::Error> {
-        // Poll on DIO and short-circuit if not asserted
-        #[cfg(feature = "poll_irq")]
-        if self.hal.get_dio()? == PinState::Low {
-            return Ok(false);
+    /// Restore the configured long preamble mode following wake-on-radio,
+    /// called on starting any subsequent receive or transmit
+    fn end_wor(&mut self) -> Result<(), <Hal as base::HalError>::E> {
+        if !self.wor_active {
+            return Ok(());
         }
 
-        let irq = self.get_interrupts(true)?;
-        let mut res = Ok(false);
-
-        trace!("RX poll (irq: {:?})", irq);
+        self.wor_active = false;
+        self.write_cmd(
+            Commands::SetLongPreamble as u8,
+            &[self.config.long_preamble as u8],
+        )
+    }
 
-        // Process flags
-        if irq.contains(Irq::CRC_ERROR) {
-            debug!("RX CRC error");
-            res = Err(Error::InvalidCrc);
-        } else if irq.contains(Irq::RX_TX_TIMEOUT) {
-            debug!("RX timeout");
-            res = Err(Error::Timeout);
-        } else if irq.contains(Irq::SYNCWORD_ERROR) {
-            debug!("Invalid syncword");
-            res = Err(Error::InvalidSync);
-        } else if irq.contains(Irq::RX_DONE) {
-            debug!("RX complete");
-            res = Ok(true);
+    /// Start duty cycled receive, periodically waking to listen for `rx` then
+    /// sleeping for `sleep` until a packet is received.
+    ///
+    /// The device uses a single step size for both windows, so configurable
+    /// timeouts must share the same step. `Timeout::Continuous` for the `rx`
+    /// window maps to the special value remaining in RX, while `Timeout::Single`
+    /// is not meaningful here and returns `Error::InvalidConfiguration`.
+    pub fn start_rx_duty_cycle(
+        &mut self,
+        rx: Timeout,
+        sleep: Timeout,
+    ) -> Result<(), <Hal as base::HalError>::E> {
+        if rx == Timeout::Single || sleep == Timeout::Single {
+            warn!("RX duty cycle does not support single mode timeouts");
+            return Err(Error::InvalidConfiguration);
         }
 
-        // Auto-restart on failure if enabled
-        match (restart, res) {
-            (true, Err(_)) => {
-                debug!("RX restarting");
-                self.start_receive()?;
-                Ok(false)
+        debug!("RX duty cycle start (rx: {:?}, sleep: {:?})", rx, sleep);
+
+        self.prepare_receive()?;
+        self.set_rx_duty_cycle(&rx, &sleep)
+    }
+
+    /// Issue a `SetRxDutyCycle` command with the provided RX and sleep windows
+    fn set_rx_duty_cycle(
+        &mut self,
+        rx: &Timeout,
+        sleep: &Timeout,
+    ) -> Result<(), <Hal as base::HalError>::E> {
+        let step = match (rx, sleep) {
+            (Timeout::Configurable { step: a, .. }, Timeout::Configurable { step: b, .. })
+                if a != b =>
+            {
+                warn!("RX duty cycle windows must share the same step size");
+                return Err(Error::InvalidConfiguration);
             }
-            (_, r) => r,
-        }
+            (Timeout::Configurable { step, .. }, _) | (_, Timeout::Configurable { step, .. }) => {
+                *step
+            }
+            _ => rx.step(),
+        };
+
+        self.write_cmd(
+            Commands::SetRxDutyCycle as u8,
+            &[
+                step as u8,
+                (rx.count() >> 8) as u8,
+                rx.count() as u8,
+                (sleep.count() >> 8) as u8,
+                sleep.count() as u8,
+            ],
+        )
     }
 
-    /// Fetch a received packet
-    fn get_received(&mut self, data: &mut [u8]) -> Result<(usize, Self::Info), Self::Error> {
-        // Fetch RX buffer information
-        let (ptr, len) = self.get_rx_buffer_status()?;
+    /// Issue a `SetRx` command with the provided timeout
+    pub(crate) fn set_rx(&mut self, timeout: &Timeout) -> Result<(), <Hal as base::HalError>::E> {
+        let config = [
+            timeout.step() as u8,
+            ((timeout.count() >> 8) & 0x00FF) as u8,
+            (timeout.count() & 0x00FF) as u8,
+        ];
 
-        debug!("RX get received, ptr: {} len: {}", ptr, len);
+        self.write_cmd(Commands::SetRx as u8, &config)
+    }
 
-        if data.len() < len as usize {
-            return Err(Error::InvalidLength);
-        }
+    /// Issue a `SetTx` command with the provided timeout
+    pub(crate) fn set_tx(&mut self, timeout: &Timeout) -> Result<(), <Hal as base::HalError>::E> {
+        let config = [
+            timeout.step() as u8,
+            ((timeout.count() >> 8) & 0x00FF) as u8,
+            (timeout.count() & 0x00FF) as u8,
+        ];
 
-        // TODO: check error packet status byte to ensure CRC is valid
-        // as this may not result in a CRC error IRQ.
-        // See chip errata for further details
+        self.write_cmd(Commands::SetTx as u8, &config)
+    }
 
-        // Read from the buffer at the provided pointer
-        self.hal.read_buff(ptr, &mut data[..len as usize])?;
+    /// Start a high-throughput transmit session using the current configuration,
+    /// see [`session`] for details
+    pub fn tx_session(&mut self) -> Result<TxSession<'_, Hal>, <Hal as base::HalError>::E> {
+        TxSession::new(self)
+    }
 
-        // Fetch related information
-        let mut info = Self::Info::default();
-        self.get_packet_info(&mut info)?;
+    /// Start a high-throughput receive session using the current configuration,
+    /// see [`session`] for details
+    pub fn rx_session(&mut self) -> Result<RxSession<'_, Hal>, <Hal as base::HalError>::E> {
+        RxSession::new(self)
+    }
 
-        trace!("RX data: {:?} info: {:?}", &data[..len as usize], info);
+    /// Lightweight receive restart, clearing interrupts and re-entering RX
+    /// using the existing modem, buffer and IRQ configuration.
+    ///
+    /// This must only be called following a `start_receive` and is used by
+    /// `check_receive` when `restart` is enabled to avoid re-running the full
+    /// receive configuration on each restart.
+    pub fn rearm_receive(&mut self) -> Result<(), <Hal as base::HalError>::E> {
+        debug!("RX rearm");
 
-        // Return read length
-        Ok((len as usize, info))
+        self.write_cmd(Commands::ClearIrqStatus as u8, &[0xFF, 0xFF])?;
+        self.rx_header_valid = false;
+
+        let timeout = self.config.rf_timeout.clone();
+        self.set_rx(&timeout)
     }
-}
 
-/// `radio::Rssi` implementation for the SX128x
-impl<Hal> radio::Rssi for Sx128x<Hal>
-where
-    Hal: base::Hal,
-{
-    type Error = <Hal as base::HalError>::E;
+    /// Start radio in continuous receive mode, where the radio remains in RX
+    /// and re-arms itself after each packet without MCU intervention.
+    ///
+    /// This is supported for all packet types, received packets are fetched with
+    /// `check_receive(false)` and `get_received` as usual (and `check_receive` should
+    /// not be called with `restart` enabled). Note the chip errata regarding
+    /// the radio becoming unresponsive in high-traffic conditions in this mode.
+    pub fn start_receive_continuous(&mut self) -> Result<(), <Hal as base::HalError>::E> {
+        self.start_receive_timeout(&Timeout::Continuous)
+    }
 
-    /// Poll for the current channel RSSI
-    /// This should only be called when in receive mode
-    fn poll_rssi(&mut self) -> Result<i16, <Hal as base::HalError>::E> {
-        let mut raw = [0u8; 1];
-        self.hal.read_cmd(Commands::GetRssiInst as u8, &mut raw)?;
-        Ok(-(raw[0] as i16) / 2)
+    /// Fetch the estimated frequency error of the last received packet in Hz,
+    /// for correcting crystal drift between radios (LoRa / Ranging only).
+    ///
+    /// This applies the datasheet formula `1.55 * FEI / (1600 / BW_kHz)`
+    /// to the sign-extended 20-bit frequency error indicator
+    #[cfg(feature = "modem-lora")]
+    pub fn get_frequency_error(&mut self) -> Result<i32, <Hal as base::HalError>::E> {
+        let bw = match (&self.packet_type, &self.config.channel) {
+            (PacketType::LoRa, Channel::LoRa(c)) => c.bw,
+            #[cfg(feature = "modem-ranging")]
+            (PacketType::Ranging, Channel::Ranging(c)) => c.bw,
+            _ => {
+                warn!("Frequency error requires LoRa or Ranging mode");
+                return Err(Error::InvalidConfiguration);
+            }
+        };
+
+        let mut data = [0u8; 3];
+        self.hal
+            .read_regs(Registers::LrEstimatedFrequencyErrorMsb as u16, &mut data)?;
+
+        let raw = ((data[0] as u32) << 16 | (data[1] as u32) << 8 | data[2] as u32)
+            & MASK_LR_ESTIMATED_FREQUENCY_ERROR;
+
+        // Sign-extend from 20 bits
+        let fei = ((raw << 12) as i32) >> 12;
+
+        let hz = fei as i64 * 155 * bw.get_bw_hz() as i64 / (100 * 1_600_000);
+
+        trace!("Frequency error: {} ({} Hz)", fei, hz);
+
+        Ok(hz as i32)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    #[test]
-    fn it_works() {
-        assert_eq!(2 + 2, 4);
+    /// Set the ranging device (responder) and request (initiator) addresses
+    #[cfg(feature = "modem-ranging")]
+    pub fn set_ranging_addresses(
+        &mut self,
+        device_addr: u32,
+        request_addr: u32,
+    ) -> Result<(), <Hal as base::HalError>::E> {
+        trace!(
+            "Set ranging addresses (device: 0x{:x}, request: 0x{:x})",
+            device_addr,
+            request_addr
+        );
+
+        self.hal.write_regs(
+            Registers::LrDeviceRangingAddr as u16,
+            &device_addr.to_be_bytes(),
+        )?;
+        self.hal.write_regs(
+            Registers::LrRequestRangingAddr as u16,
+            &request_addr.to_be_bytes(),
+        )
+    }
+
+    /// Fetch the result of the last ranging exchange as a distance in meters,
+    /// converted using the configured ranging bandwidth.
+    ///
+    /// Results are frozen while being read to avoid tearing, returning
+    /// `Error::InvalidConfiguration` outside of ranging mode
+    #[cfg(feature = "modem-ranging")]
+    pub fn get_ranging_result(
+        &mut self,
+        result_type: RangingResultType,
+    ) -> Result<f32, <Hal as base::HalError>::E> {
+        let bw = match (&self.packet_type, &self.config.channel) {
+            (PacketType::Ranging, Channel::Ranging(c)) => c.bw,
+            _ => {
+                warn!("Ranging result requires Ranging mode");
+                return Err(Error::InvalidConfiguration);
+            }
+        };
+
+        // Freeze results and select the result type
+        let freeze = self
+            .hal
+            .read_reg(Registers::LrRangingResultsFreeze as u16)?;
+        self.hal.write_reg(
+            Registers::LrRangingResultsFreeze as u16,
+            freeze | MASK_RANGING_RESULTS_FREEZE,
+        )?;
+
+        let config = self.hal.read_reg(Registers::LrRangingResultConfig as u16)?;
+        self.hal.write_reg(
+            Registers::LrRangingResultConfig as u16,
+            (config & MASK_RANGINGMUXSEL) | ((result_type as u8) << 4),
+        )?;
+
+        let mut data = [0u8; 3];
+        self.hal
+            .read_regs(Registers::LrRangingResultBaseAddr as u16, &mut data)?;
+
+        self.hal.write_reg(
+            Registers::LrRangingResultsFreeze as u16,
+            freeze & !MASK_RANGING_RESULTS_FREEZE,
+        )?;
+
+        let raw = (data[0] as u32) << 16 | (data[1] as u32) << 8 | data[2] as u32;
+
+        let distance = match result_type {
+            // Sign-extend from 24 bits, distance = raw * 150 / (2^12 * BW_MHz)
+            RangingResultType::Raw => {
+                (((raw << 8) as i32) >> 8) as f32 * 36_621.094 / bw.get_bw_hz() as f32
+            }
+            RangingResultType::Averaged | RangingResultType::DebugFiltered => raw as f32 * 0.2,
+        };
+
+        trace!("Ranging result: {} ({} m)", raw, distance);
+
+        Ok(distance)
+    }
+
+    /// Start transmitting an unmodulated carrier on the configured channel, for
+    /// antenna tuning and regulatory testing. Stop with `stop_continuous`.
+    pub fn start_tx_continuous_wave(&mut self) -> Result<(), <Hal as base::HalError>::E> {
+        debug!("TX continuous wave start");
+
+        self.start_tx_continuous(Commands::SetTxContinuousWave)
+    }
+
+    /// Start transmitting a continuous preamble using the configured modem,
+    /// for testing. Stop with `stop_continuous`.
+    pub fn start_tx_continuous_preamble(&mut self) -> Result<(), <Hal as base::HalError>::E> {
+        debug!("TX continuous preamble start");
+
+        self.start_tx_continuous(Commands::SetTxContinuousPreamble)
+    }
+
+    /// Stop continuous wave or preamble transmission, returning to standby
+    pub fn stop_continuous(&mut self) -> Result<(), <Hal as base::HalError>::E> {
+        debug!("TX continuous stop");
+
+        self.set_state(State::StandbyRc)
+    }
+
+    fn start_tx_continuous(&mut self, command: Commands) -> Result<(), <Hal as base::HalError>::E> {
+        // Test modes replace any preloaded beacon
+        self.beacon_interval = None;
+
+        self.set_state(State::StandbyRc)?;
+
+        // Apply the current channel and power configuration
+        let channel = self.config.channel.clone();
+        self.set_channel(&channel)?;
+
+        let pa = self.config.pa_config.clone();
+        self.set_power_ramp(pa.power, pa.ramp_time)?;
+
+        self.write_cmd(command as u8, &[])
+    }
+
+    /// Start Channel Activity Detection using `Config::cad_params`, for
+    /// LoRa listen-before-talk. Completion is polled with `check_cad`.
+    ///
+    /// Returns `Error::InvalidConfiguration` outside of LoRa / Ranging modes
+    pub fn start_cad(&mut self) -> Result<(), <Hal as base::HalError>::E> {
+        if !matches!(self.packet_type, PacketType::LoRa | PacketType::Ranging) {
+            warn!("CAD requires LoRa or Ranging mode");
+            return Err(Error::InvalidConfiguration);
+        }
+
+        let params = self.config.cad_params;
+        debug!("CAD start ({:?})", params);
+
+        self.set_state(State::StandbyRc)?;
+
+        self.write_cmd(Commands::SetCadParams as u8, &[params.symbols as u8])?;
+
+        // Enable IRQs
+        let irqs = Irq::CAD_DONE | Irq::CAD_ACTIVITY_DETECTED;
+        self.set_irq_dio_mask(irqs, irqs, DioMask::empty(), DioMask::empty())?;
+
+        self.write_cmd(Commands::SetCad as u8, &[])
+    }
+
+    /// Poll for radio events, reading and clearing interrupts once and returning
+    /// the highest priority event (see `RadioEvent::from_irq`) or `None`.
+    ///
+    /// This is intended to drive state machines in place of the separate
+    /// `check_transmit` / `check_receive` / `get_interrupts` calls, lower priority
+    /// flags raised alongside the returned event are cleared and not reported
+    pub fn poll(&mut self) -> Result<Option<RadioEvent>, <Hal as base::HalError>::E> {
+        let irq = self.get_interrupts(true)?;
+
+        let event = RadioEvent::from_irq(irq);
+        if let Some(e) = &event {
+            debug!("Radio event: {:?} (irq: {:?})", e, irq);
+        }
+
+        Ok(event)
+    }
+
+    /// Poll for Channel Activity Detection completion, returning whether
+    /// activity was detected on completion or `None` while in progress.
+    ///
+    /// With `CadExitMode::Receive` receive is started when activity is detected
+    pub fn check_cad(&mut self) -> Result<Option<bool>, <Hal as base::HalError>::E> {
+        let irq = self.get_interrupts(true)?;
+
+        trace!("CAD poll (irq: {:?})", irq);
+
+        if !irq.contains(Irq::CAD_DONE) {
+            return Ok(None);
+        }
+
+        let activity = irq.contains(Irq::CAD_ACTIVITY_DETECTED);
+        debug!("CAD complete (activity: {})", activity);
+
+        if activity && self.config.cad_params.exit_mode == CadExitMode::Receive {
+            self.start_receive()?;
+        }
+
+        Ok(Some(activity))
+    }
+
+    /// Check whether the channel is clear for listen-before-talk, running a single
+    /// CAD with the provided number of symbols and blocking until completion or
+    /// `Config::timeout_ms` elapses (returning `Error::Timeout`)
+    ///
+    /// The prior standby, FS or RX state is restored afterwards (LoRa / Ranging only)
+    pub fn is_channel_clear(
+        &mut self,
+        symbols: CadSymbols,
+    ) -> Result<bool, <Hal as base::HalError>::E> {
+        let prior = self.get_state()?;
+        if prior == State::Tx {
+            warn!("CAD unavailable during transmit");
+            return Err(Error::InvalidState(State::StandbyRc, prior));
+        }
+
+        // Run CAD without the receive exit mode, restoring parameters afterwards
+        let params = self.config.cad_params;
+        self.config.cad_params = CadParams {
+            symbols,
+            exit_mode: CadExitMode::Standby,
+        };
+
+        let res = self.start_cad().and_then(|_| {
+            for _ in 0..self.config.timeout_ms {
+                if let Some(activity) = self.check_cad()? {
+                    return Ok(!activity);
+                }
+                self.hal.delay_ms(1);
+            }
+
+            warn!("CAD timeout");
+            self.set_state(State::StandbyRc)?;
+
+            Err(Error::Timeout)
+        });
+
+        self.config.cad_params = params;
+        let clear = res?;
+
+        match prior {
+            State::Rx => self.start_receive()?,
+            State::StandbyXosc | State::Fs => self.set_state(prior)?,
+            _ => (),
+        }
+
+        Ok(clear)
+    }
+
+    /// Wait for DIO to be asserted, polling every millisecond for up to
+    /// `timeout_ms` and returning `Error::Timeout` if it is not
+    ///
+    /// See `Hal::dio_asserted` for edge / level semantics
+    pub fn wait_dio_high(&mut self, timeout_ms: u32) -> Result<(), <Hal as base::HalError>::E> {
+        let mut elapsed = 0;
+        while !self.hal.dio_asserted()? {
+            if elapsed >= timeout_ms {
+                debug!("DIO timeout after {} ms", timeout_ms);
+                return Err(Error::Timeout);
+            }
+
+            self.hal.delay_ms(1);
+            elapsed += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Poll for transmit completion, distinguishing an in-progress transmission
+    /// from completion or timeout.
+    ///
+    /// With `Config::detect_packet_controller_busy` a timeout with the packet
+    /// controller stuck busy returns `Error::PacketControllerBusy`, as this
+    /// requires a device reset.
+    ///
+    /// This polls the busy pin without blocking, returning `InProgress` while
+    /// the device is busy (as does `check_receive`).
+    pub fn poll_transmit(&mut self) -> Result<TransmitStatus, <Hal as base::HalError>::E> {
+        // Poll on DIO and short-circuit if not asserted
+        #[cfg(feature = "poll-irq")]
+        if !self.hal.dio_asserted()? {
+            return Ok(TransmitStatus::InProgress);
+        }
+
+        // Short-circuit rather than blocking while the device is busy
+        if !self.hal.try_wait_busy()? {
+            return Ok(TransmitStatus::InProgress);
+        }
+
+        let irq = self.get_interrupts(true)?;
+        let state = self.get_state()?;
+
+        trace!("TX poll (irq: {:?}, state: {:?})", irq, state);
+
+        if irq.contains(Irq::TX_DONE) {
+            debug!("TX complete");
+            Ok(TransmitStatus::Done)
+        } else if irq.contains(Irq::RX_TX_TIMEOUT) {
+            debug!("TX timeout");
+            match self.check_packet_controller()? {
+                true => Err(Error::PacketControllerBusy),
+                false => Ok(TransmitStatus::Timeout),
+            }
+        } else {
+            Ok(TransmitStatus::InProgress)
+        }
+    }
+
+    /// Run a receive loop, calling `on_packet` for each received packet until
+    /// it returns `LoopControl::Stop`.
+    ///
+    /// Receive is polled every `poll_us` microseconds. Soft errors (CRC, sync and
+    /// RX timeouts, and packet status errors) re-arm receive and continue, while
+    /// hard errors (comms, pin, or a stuck packet controller) are returned.
+    pub fn receive_loop<F>(
+        &mut self,
+        buf: &mut [u8],
+        poll_us: u32,
+        mut on_packet: F,
+    ) -> Result<(), <Hal as base::HalError>::E>
+    where
+        F: FnMut(&[u8], &PacketInfo) -> LoopControl,
+    {
+        self.start_receive()?;
+
+        loop {
+            // Soft errors are handled by check_receive with restart enabled
+            if self.check_receive(true)? {
+                let (n, info) = match self.get_received(buf) {
+                    Ok(v) => v,
+                    // Packet status errors are soft, discard the packet and re-arm
+                    Err(
+                        Error::InvalidCrc
+                        | Error::InvalidSync
+                        | Error::InvalidLength
+                        | Error::Abort,
+                    ) => {
+                        if self.config.rf_timeout != Timeout::Continuous {
+                            self.rearm_receive()?;
+                        }
+                        self.hal.delay_us(poll_us);
+                        continue;
+                    }
+                    Err(e) => return Err(e),
+                };
+
+                if on_packet(&buf[..n], &info) == LoopControl::Stop {
+                    return Ok(());
+                }
+
+                // Continuous mode remains in RX following reception
+                if self.config.rf_timeout != Timeout::Continuous {
+                    self.rearm_receive()?;
+                }
+            }
+
+            self.hal.delay_us(poll_us);
+        }
+    }
+
+    /// Block until the current transmission completes, or `Config::timeout_ms` elapses
+    pub(crate) fn wait_transmit(&mut self) -> Result<(), <Hal as base::HalError>::E> {
+        for _ in 0..self.config.timeout_ms {
+            if self.check_transmit()? {
+                return Ok(());
+            }
+            self.hal.delay_ms(1);
+        }
+
+        Err(Error::Timeout)
+    }
+
+    /// Block until a packet is received, or `Config::timeout_ms` elapses
+    pub(crate) fn wait_receive(&mut self) -> Result<(), <Hal as base::HalError>::E> {
+        for _ in 0..self.config.timeout_ms {
+            if self.check_receive(false)? {
+                return Ok(());
+            }
+            self.hal.delay_ms(1);
+        }
+
+        Err(Error::Timeout)
+    }
+
+    /// Transmit a packet then immediately receive a reply, for request /
+    /// response protocols.
+    ///
+    /// Following `Irq::TX_DONE` the radio has already returned to standby with
+    /// the modem configured, so receive is armed with only the RX IRQ mask and
+    /// `SetRx` commands. Turnaround is therefore two SPI transactions plus up to
+    /// 1 ms of TX completion polling (the SX128x supports automatic RX to TX
+    /// with `AutoTx`, but has no automatic TX to RX equivalent).
+    ///
+    /// Receive completes on reception, or returns `Error::Timeout` on expiry
+    /// of `rx_timeout` or where no reply is received within `Config::timeout_ms`
+    /// (bounding `Timeout::Single` and `Timeout::Continuous`).
+    pub fn transceive(
+        &mut self,
+        tx: &[u8],
+        rx_buf: &mut [u8],
+        rx_timeout: Timeout,
+    ) -> Result<(usize, PacketInfo), <Hal as base::HalError>::E> {
+        self.start_transmit(tx)?;
+        self.wait_transmit()?;
+
+        // Arm receive directly from post-TX standby
+        let irqs = Irq::RX_DEFAULT;
+        self.set_irq_dio_mask(irqs, irqs, DioMask::empty(), DioMask::empty())?;
+        self.rx_header_valid = false;
+        self.set_rx(&rx_timeout)?;
+
+        debug!("Transceive RX started");
+
+        self.wait_receive()?;
+        self.get_received(rx_buf)
+    }
+
+    /// Start a periodic beacon, preloading the payload and sending the first beacon.
+    ///
+    /// The SX128x cannot autonomously transmit on a timer (`AutoTx` only transmits
+    /// following a reception and RX duty cycling only applies to receive), so the MCU
+    /// must wake every `interval` (see `beacon_interval`) and call `beacon`, which
+    /// re-transmits the preloaded payload with a single `SetTx` command. Between
+    /// beacons the radio returns to standby retaining the payload, so no further
+    /// configuration or buffer writes are required until the radio is reconfigured,
+    /// put to sleep, or another packet is sent or received (which stops the beacon).
+    pub fn start_beacon(
+        &mut self,
+        payload: &[u8],
+        interval: Timeout,
+    ) -> Result<(), <Hal as base::HalError>::E> {
+        if interval.as_micros().is_none() {
+            warn!("Beacon interval must be a configurable timeout");
+            return Err(Error::InvalidConfiguration);
+        }
+
+        debug!("Starting beacon (interval: {:?})", interval);
+
+        self.start_transmit(payload)?;
+        self.beacon_interval = Some(interval);
+
+        Ok(())
+    }
+
+    /// Fetch the active beacon interval, for scheduling MCU wake-ups
+    pub fn beacon_interval(&self) -> Option<&Timeout> {
+        self.beacon_interval.as_ref()
+    }
+
+    /// Re-transmit the preloaded beacon payload, completion may be polled with
+    /// `check_transmit` as usual
+    pub fn beacon(&mut self) -> Result<(), <Hal as base::HalError>::E> {
+        if self.beacon_interval.is_none() {
+            warn!("Beacon not started");
+            return Err(Error::InvalidConfiguration);
+        }
+
+        let timeout = self.config.rf_timeout.clone();
+        self.set_tx(&timeout)
+    }
+
+    /// Stop the periodic beacon
+    pub fn stop_beacon(&mut self) {
+        self.beacon_interval = None;
+    }
+
+    /// Transmit a payload larger than a single packet by splitting it into fragments
+    /// of at most `mtu` bytes, sent back-to-back.
+    ///
+    /// Each fragment is prefixed with a one byte header, the lower seven bits containing
+    /// the fragment sequence number (starting from 0) and the top bit set on the final
+    /// fragment, allowing up to 128 fragments of `mtu - 1` data bytes.
+    ///
+    /// This blocks until each fragment is sent, `Config::timeout_ms` must exceed the
+    /// time-on-air of a single fragment.
+    pub fn transmit_fragmented(
+        &mut self,
+        data: &[u8],
+        mtu: u8,
+    ) -> Result<(), <Hal as base::HalError>::E> {
+        let chunk = (mtu as usize).saturating_sub(FRAGMENT_HEADER_LEN);
+        if chunk == 0 {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        let count = core::cmp::max(1, data.len().div_ceil(chunk));
+        if count > FRAGMENT_SEQ_MASK as usize + 1 {
+            return Err(Error::InvalidLength);
+        }
+
+        debug!("TX fragmented ({} bytes, {} fragments)", data.len(), count);
+
+        let mut buff = [0u8; 256];
+        for seq in 0..count {
+            let start = seq * chunk;
+            let end = core::cmp::min(start + chunk, data.len());
+            let len = FRAGMENT_HEADER_LEN + end - start;
+
+            buff[0] = match seq == count - 1 {
+                true => seq as u8 | FRAGMENT_LAST,
+                false => seq as u8,
+            };
+            buff[FRAGMENT_HEADER_LEN..len].copy_from_slice(&data[start..end]);
+
+            self.start_transmit(&buff[..len])?;
+            self.wait_transmit()?;
+        }
+
+        Ok(())
+    }
+
+    /// Check a received packet is pending, returning `Error::Aborted` if the
+    /// current packet has already been read
+    fn check_rx_pending(&self) -> Result<(), <Hal as base::HalError>::E> {
+        if !self.rx_pending {
+            warn!("RX packet already consumed");
+            return Err(Error::Aborted);
+        }
+        Ok(())
+    }
+
+    /// Fetch a received BLE packet, returning the PDU header separately from the
+    /// payload along with the payload length and packet information.
+    ///
+    /// BLE packets are received as a 2-byte PDU header followed by the PDU payload,
+    /// the header byte contains the PDU type and address flags (see `BlePduHeader`)
+    /// and the length byte the payload length. `get_received` returns these
+    /// combined, this splits the header off so the payload is written to the start
+    /// of the provided buffer.
+    ///
+    /// Returns `Error::InvalidConfiguration` if the radio is not configured for BLE.
+    #[cfg(feature = "modem-ble")]
+    pub fn get_received_ble(
+        &mut self,
+        data: &mut [u8],
+    ) -> Result<(BlePduHeader, usize, PacketInfo), <Hal as base::HalError>::E> {
+        if !matches!(self.config.modem, Modem::Ble(_)) {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        self.check_rx_pending()?;
+
+        // Fetch RX buffer information, length includes the PDU header
+        let RxBufferStatus { ptr, len } = self.get_rx_buffer_status()?;
+        let len = (len as usize).saturating_sub(BLE_PDU_HEADER_LEN);
+
+        if data.len() < len {
+            return Err(Error::InvalidLength);
+        }
+
+        let mut h = [0u8; BLE_PDU_HEADER_LEN];
+        self.hal.read_buff(ptr, &mut h)?;
+        let header = BlePduHeader {
+            header: h[0],
+            length: h[1],
+        };
+
+        self.hal
+            .read_buff(ptr.wrapping_add(BLE_PDU_HEADER_LEN as u8), &mut data[..len])?;
+        self.rx_pending = false;
+
+        let mut info = PacketInfo::default();
+        self.get_packet_info(&mut info)?;
+        check_packet_status(self.packet_type, &self.config, &mut info)?;
+
+        trace!(
+            "RX BLE header: {:?} data: {:?} info: {:?}",
+            header,
+            &data[..len],
+            info
+        );
+
+        Ok((header, len, info))
+    }
+
+    /// Receive and reassemble a payload sent with `transmit_fragmented` into the
+    /// provided buffer, returning the reassembled length.
+    ///
+    /// This blocks until each fragment is received, returning `Error::Aborted` if a
+    /// fragment is missed or out of order and `Error::InvalidLength` if the buffer
+    /// is too small.
+    pub fn receive_reassembled(
+        &mut self,
+        data: &mut [u8],
+    ) -> Result<usize, <Hal as base::HalError>::E> {
+        let mut buff = [0u8; 256];
+        let mut n = 0;
+        let mut expected = 0;
+
+        loop {
+            self.start_receive()?;
+            self.wait_receive()?;
+
+            let (len, _info) = self.get_received(&mut buff)?;
+            if len < FRAGMENT_HEADER_LEN {
+                return Err(Error::InvalidLength);
+            }
+
+            let header = buff[0];
+            if header & FRAGMENT_SEQ_MASK != expected {
+                warn!(
+                    "Unexpected fragment (expected: {} actual: {})",
+                    expected,
+                    header & FRAGMENT_SEQ_MASK
+                );
+                return Err(Error::Aborted);
+            }
+
+            let payload = &buff[FRAGMENT_HEADER_LEN..len];
+            if n + payload.len() > data.len() {
+                return Err(Error::InvalidLength);
+            }
+            data[n..n + payload.len()].copy_from_slice(payload);
+            n += payload.len();
+
+            if header & FRAGMENT_LAST != 0 {
+                debug!("RX reassembled ({} bytes, {} fragments)", n, expected + 1);
+                return Ok(n);
+            }
+
+            expected += 1;
+        }
+    }
+
+    /// Apply patch for sync-word match errata in FLRC mode
+    #[cfg(feature = "modem-flrc")]
+    fn patch_flrc_syncword(&mut self) -> Result<(), <Hal as base::HalError>::E> {
+        // If we're in FLRC mode, patch to force 100% match on syncwords
+        // because otherwise the 4 bit threshold is too low
+        if let PacketType::Flrc = &self.packet_type {
+            let r = self.hal.read_reg(Registers::LrSyncWordTolerance as u16)?;
+            self.hal.write_reg(
+                Registers::LrSyncWordTolerance as u16,
+                transactions::patch_flrc_sync_word_tolerance(r),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<Hal> DelayNs for Sx128x<Hal>
+where
+    Hal: base::Hal,
+{
+    fn delay_ns(&mut self, t: u32) {
+        self.hal.delay_ns(t);
+    }
+}
+
+/// `radio::State` implementation for the SX128x
+impl<Hal> radio::State for Sx128x<Hal>
+where
+    Hal: base::Hal,
+{
+    type State = State;
+    type Error = <Hal as base::HalError>::E;
+
+    /// Fetch device state
+    fn get_state(&mut self) -> Result<Self::State, Self::Error> {
+        let (m, _s) = self.get_status()?;
+        Ok(m)
+    }
+
+    /// Set device state
+    fn set_state(&mut self, state: Self::State) -> Result<(), Self::Error> {
+        let command = match state {
+            State::Tx => Commands::SetTx,
+            State::Rx => Commands::SetRx,
+            //State::Cad => Commands::SetCad,
+            State::Fs => Commands::SetFs,
+            State::StandbyRc | State::StandbyXosc => Commands::SetStandby,
+            State::Sleep => return self.sleep(SleepConfig::empty()),
+            State::Unknown => {
+                warn!("Unknown state cannot be set");
+                return Err(Error::InvalidStateCommand);
+            }
+        };
+
+        trace!("Setting state {:?} ({})", state, command);
+
+        self.write_cmd(command as u8, &[0u8])
+    }
+}
+
+/// `radio::Busy` implementation for the SX128x
+impl<Hal> radio::Busy for Sx128x<Hal>
+where
+    Hal: base::Hal,
+{
+    type Error = <Hal as base::HalError>::E;
+
+    /// Fetch device state
+    fn is_busy(&mut self) -> Result<bool, Self::Error> {
+        let irq = self.get_interrupts(false)?;
+
+        if irq.contains(Irq::SYNCWORD_VALID)
+            && !(irq.contains(Irq::RX_DONE) || irq.contains(Irq::CRC_ERROR))
+        {
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+}
+
+/// `radio::Channel` implementation for the SX128x
+impl<Hal> radio::Channel for Sx128x<Hal>
+where
+    Hal: base::Hal,
+{
+    /// Channel consists of an operating frequency and packet mode
+    type Channel = Channel;
+
+    type Error = <Hal as base::HalError>::E;
+
+    /// Set operating channel
+    fn set_channel(&mut self, ch: &Self::Channel) -> Result<(), Self::Error> {
+        debug!("Setting channel config: {:?}", ch);
+
+        // Check the channel edges (accounting for bandwidth) lie within the band
+        let (low, high) = ch.edges_hz();
+        if low < FREQ_MIN || high > FREQ_MAX {
+            warn!(
+                "Channel {}-{} Hz outside band {}-{} Hz",
+                low, high, FREQ_MIN, FREQ_MAX
+            );
+            return Err(Error::InvalidFrequency);
+        }
+
+        // Write frequency, packet type (if required) and modulation configuration,
+        // matching `transactions::set_channel`
+        self.set_frequency(ch.frequency())?;
+        self.ensure_packet_type(PacketType::from(ch))?;
+        self.write_transaction(&transactions::set_modulation_params(ch))?;
+
+        // Force modem reconfiguration on the next receive
+        self.applied_modem = None;
+
+        Ok(())
+    }
+}
+
+/// `radio::Power` implementation for the SX128x
+impl<Hal> radio::Power for Sx128x<Hal>
+where
+    Hal: base::Hal,
+{
+    type Error = <Hal as base::HalError>::E;
+
+    /// Set TX power in dBm
+    fn set_power(&mut self, power: i8) -> Result<(), <Hal as base::HalError>::E> {
+        let ramp_time = self.config.pa_config.ramp_time;
+        self.set_power_ramp(power, ramp_time)
+    }
+}
+
+/// `radio::Interrupts` implementation for the SX128x
+impl<Hal> radio::Interrupts for Sx128x<Hal>
+where
+    Hal: base::Hal,
+{
+    type Irq = Irq;
+    type Error = <Hal as base::HalError>::E;
+
+    /// Fetch (and optionally clear) current interrupts
+    fn get_interrupts(&mut self, clear: bool) -> Result<Self::Irq, Self::Error> {
+        let mask = match clear {
+            true => Irq::all(),
+            false => Irq::empty(),
+        };
+
+        self.get_interrupts_masked(mask)
+    }
+}
+
+/// `radio::Transmit` implementation for the SX128x
+impl<Hal> radio::Transmit for Sx128x<Hal>
+where
+    Hal: base::Hal,
+{
+    type Error = <Hal as base::HalError>::E;
+
+    /// Start transmitting a packet
+    fn start_transmit(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        debug!("TX start");
+
+        let max_len = self.config.modem.max_payload_len();
+        if data.len() > max_len {
+            warn!(
+                "TX payload length {} exceeds maximum {}",
+                data.len(),
+                max_len
+            );
+            return Err(Error::InvalidLength);
+        }
+
+        // Overwriting the buffer invalidates any preloaded beacon
+        self.beacon_interval = None;
+
+        // Set state to idle before we write configuration
+        self.set_state(self.idle_state())?;
+        self.end_wor()?;
+
+        let s = self.get_state()?;
+        debug!("TX setup state: {:?}", s);
+
+        // Set packet mode
+        let mut modem_config = self.config.modem.clone();
+        modem_config.set_payload_len(data.len() as u8);
+
+        if let Err(e) = self.configure_modem(&modem_config) {
+            if let Ok(s) = self.get_state() {
+                error!("TX error setting modem (state: {:?})", s);
+            } else {
+                error!("TX error setting modem",);
+            }
+            return Err(e);
+        }
+
+        // Reset buffer addr
+        if let Err(e) = self.set_buff_base_addr(self.config.tx_base_addr, self.config.rx_base_addr)
+        {
+            if let Ok(s) = self.get_state() {
+                error!("TX error setting buffer base addr (state: {:?})", s);
+            } else {
+                error!("TX error setting buffer base addr",);
+            }
+
+            return Err(e);
+        }
+
+        // Write data to be sent
+        debug!("TX data: {:?}", data);
+        self.hal.write_buff(self.config.tx_base_addr, data)?;
+
+        // Configure ranging if used
+        if PacketType::Ranging == self.packet_type {
+            self.write_cmd(
+                Commands::SetRangingRole as u8,
+                &[RangingRole::Initiator as u8],
+            )?;
+        }
+
+        // Enable IRQs
+        let irqs = Irq::TX_DEFAULT;
+        self.set_irq_dio_mask(irqs, irqs, DioMask::empty(), DioMask::empty())?;
+
+        // Enter transmit mode
+        let timeout = self.config.rf_timeout.clone();
+        self.set_tx(&timeout)?;
+
+        trace!("TX start issued");
+
+        let state = self.get_state()?;
+        trace!("State: {:?}", state);
+
+        Ok(())
+    }
+
+    /// Check for transmit completion
+    fn check_transmit(&mut self) -> Result<bool, Self::Error> {
+        match self.poll_transmit()? {
+            TransmitStatus::Done => Ok(true),
+            TransmitStatus::InProgress => Ok(false),
+            TransmitStatus::Timeout => Err(Error::Timeout),
+        }
+    }
+}
+
+/// `radio::Receive` implementation for the SX128x
+impl<Hal> radio::Receive for Sx128x<Hal>
+where
+    Hal: base::Hal,
+{
+    /// Receive info structure
+    type Info = PacketInfo;
+
+    /// RF Error object
+    type Error = <Hal as base::HalError>::E;
+
+    /// Start radio in receive mode
+    fn start_receive(&mut self) -> Result<(), Self::Error> {
+        let timeout = self.config.rf_timeout.clone();
+        self.start_receive_timeout(&timeout)
+    }
+
+    /// Check for a received packet
+    ///
+    /// This returns `Ok(false)` without blocking while the device is busy
+    fn check_receive(&mut self, restart: bool) -> Result<bool, Self::Error> {
+        // Poll on DIO and short-circuit if not asserted
+        #[cfg(feature = "poll-irq")]
+        if !self.hal.dio_asserted()? {
+            return Ok(false);
+        }
+
+        // Short-circuit rather than blocking while the device is busy
+        if !self.hal.try_wait_busy()? {
+            return Ok(false);
+        }
+
+        let irq = self.get_interrupts(true)?;
+        let mut res = Ok(false);
+
+        trace!("RX poll (irq: {:?})", irq);
+
+        // Recover from RX lock-up if no receive completes within the watchdog interval
+        let done = Irq::RX_DONE | Irq::CRC_ERROR | Irq::RX_TX_TIMEOUT | Irq::SYNCWORD_ERROR;
+        if irq.intersects(done) {
+            self.rx_idle_polls = 0;
+        } else if let Some(n) = self.config.rx_watchdog_polls {
+            self.rx_idle_polls += 1;
+
+            if self.rx_idle_polls >= n {
+                self.rx_idle_polls = 0;
+
+                if self.get_state()? == State::Rx {
+                    warn!("RX watchdog expired, re-arming receive");
+                    self.set_state(State::StandbyRc)?;
+                    self.rearm_receive()?;
+                }
+            }
+        }
+
+        // Process flags
+        if irq.contains(Irq::CRC_ERROR) && self.config.deliver_crc_errors {
+            debug!("RX complete (CRC error)");
+            res = Ok(true);
+        } else if irq.contains(Irq::CRC_ERROR) {
+            debug!("RX CRC error");
+            res = Err(Error::InvalidCrc);
+        } else if irq.contains(Irq::RX_TX_TIMEOUT) {
+            debug!("RX timeout");
+            res = match self.check_packet_controller()? {
+                true => Err(Error::PacketControllerBusy),
+                false => Err(Error::Timeout),
+            };
+        } else if irq.contains(Irq::SYNCWORD_ERROR) {
+            debug!("Invalid syncword");
+            res = Err(Error::InvalidSync);
+        } else if irq.contains(Irq::RX_DONE) {
+            debug!("RX complete");
+            res = Ok(true);
+        }
+
+        // Auto-restart on failure if enabled
+        // (a wedged packet controller is not cleared by restarting so is always returned)
+        match (restart, res) {
+            (_, Err(Error::PacketControllerBusy)) => Err(Error::PacketControllerBusy),
+            (true, Err(_)) => {
+                debug!("RX restarting");
+                self.rearm_receive()?;
+                Ok(false)
+            }
+            (_, r) => r,
+        }
+    }
+
+    /// Fetch a received packet
+    ///
+    /// Returns `Error::Aborted` if no packet has been received (`Irq::RX_DONE`)
+    /// since the last call, rather than re-reading stale buffer contents
+    fn get_received(&mut self, data: &mut [u8]) -> Result<(usize, Self::Info), Self::Error> {
+        self.check_rx_pending()?;
+
+        // Fetch RX buffer information
+        let RxBufferStatus { ptr, len } = self.get_rx_buffer_status()?;
+
+        debug!("RX get received, ptr: {} len: {}", ptr, len);
+
+        if data.len() < len as usize {
+            return Err(Error::InvalidLength);
+        }
+
+        // Read from the buffer at the provided pointer
+        self.hal.read_buff(ptr, &mut data[..len as usize])?;
+        self.rx_pending = false;
+
+        // Fetch related information
+        let mut info = Self::Info::default();
+        self.get_packet_info(&mut info)?;
+        info.crc_ok = !self.rx_crc_error;
+        self.rx_header_valid = false;
+
+        // Check the packet status byte as errors may not result in an IRQ,
+        // see chip errata for further details
+        check_packet_status(self.packet_type, &self.config, &mut info)?;
+
+        trace!("RX data: {:?} info: {:?}", &data[..len as usize], info);
+
+        // Return read length
+        Ok((len as usize, info))
+    }
+}
+
+/// `radio::Rssi` implementation for the SX128x
+impl<Hal> radio::Rssi for Sx128x<Hal>
+where
+    Hal: base::Hal,
+{
+    type Error = <Hal as base::HalError>::E;
+
+    /// Poll for the current channel RSSI
+    ///
+    /// Readings are only meaningful in receive mode, so this returns
+    /// `Error::InvalidState` if the radio is not in RX, see
+    /// `Sx128x::poll_rssi_unchecked` to skip this check
+    fn poll_rssi(&mut self) -> Result<i16, <Hal as base::HalError>::E> {
+        let (state, _) = self.get_status()?;
+        if state != State::Rx {
+            warn!("RSSI polled outside of RX (state: {:?})", state);
+            return Err(Error::InvalidState(State::Rx, state));
+        }
+
+        self.poll_rssi_unchecked()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use radio::{Channel as _, Power, Receive, Transmit};
+
+    use crate::{
+        base::Hal,
+        device::{ble::*, common::*, flrc::*, gfsk::*, lora::*, *},
+        mock::MockHal,
+        Error, Sx128x,
+    };
+
+    #[test]
+    fn it_works() {
+        assert_eq!(2 + 2, 4);
+    }
+
+    #[test]
+    fn set_channel_transactions() {
+        let mut radio = Sx128x::build(MockHal::default());
+        let ch = Channel::LoRa(LoRaChannel {
+            sf: LoRaSpreadingFactor::Sf9,
+            ..Default::default()
+        });
+
+        let expected = transactions::set_channel(&radio.config, PacketType::None, &ch);
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|t| (t.prefix().to_vec(), t.data().to_vec()))
+            .collect();
+
+        assert_eq!(
+            expected,
+            vec![
+                (vec![0x86], vec![0xbb, 0xb1, 0x3b]),
+                (vec![0x8a], vec![0x01]),
+                (vec![0x8b], vec![0x90, 0x34, 0x01]),
+            ]
+        );
+
+        radio.set_channel(&ch).unwrap();
+        assert_eq!(radio.hal.writes, expected);
+    }
+
+    #[test]
+    fn set_power_out_of_range_clamp() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        radio.set_power(20).unwrap();
+
+        assert_eq!(
+            radio.hal.written(&[Commands::SetTxParams as u8]),
+            vec![vec![13 + 18, RampTime::Ramp20Us as u8]]
+        );
+    }
+
+    #[test]
+    fn start_transmit_payload_len() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.config = Config::flrc();
+
+        // Oversized payloads are rejected rather than truncated
+        assert_eq!(radio.start_transmit(&[0u8; 128]), Err(Error::InvalidLength));
+        assert!(radio.hal.writes.is_empty());
+        radio.start_transmit(&[0u8; 127]).unwrap();
+    }
+
+    #[test]
+    fn set_power_out_of_range_error() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.config.power_out_of_range = OutOfRangePolicy::Error;
+
+        assert_eq!(radio.set_power(20), Err(Error::InvalidPower(20)));
+        assert!(radio.hal.written(&[Commands::SetTxParams as u8]).is_empty());
+
+        // The range is independent of the regulator mode
+        for mode in [RegulatorMode::Ldo, RegulatorMode::Dcdc] {
+            radio.config.regulator_mode = mode;
+
+            assert_eq!(
+                radio.set_power(TX_POWER_MAX + 1),
+                Err(Error::InvalidPower(TX_POWER_MAX + 1))
+            );
+            assert_eq!(
+                radio.set_power(TX_POWER_MIN - 1),
+                Err(Error::InvalidPower(TX_POWER_MIN - 1))
+            );
+            assert_eq!(radio.set_power(TX_POWER_MAX), Ok(()));
+            assert_eq!(radio.set_power(TX_POWER_MIN), Ok(()));
+        }
+    }
+
+    #[test]
+    fn get_received_ble() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.hal.dio = true;
+        radio.config.modem = Modem::Ble(BleConfig {
+            connection_state: BleConnectionStates::BLE_PAYLOAD_LENGTH_MAX_37_BYTES,
+            crc_field: BleCrcFields::BLE_CRC_3B,
+            packet_type: BlePacketTypes::BLE_PRBS_9,
+            whitening: WhiteningModes::RADIO_WHITENING_ON,
+        });
+        radio.packet_type = PacketType::Ble;
+
+        // 3 byte payload at offset 0x10, preceded by an ADV_NONCONN_IND header
+        radio.hal.reads = vec![
+            (
+                vec![Commands::GetIrqStatus as u8, 0],
+                vec![0x00, Irq::RX_DONE.bits() as u8],
+            ),
+            (vec![Commands::GetRxBufferStatus as u8, 0], vec![3, 0x10]),
+            (vec![Commands::ReadBuffer as u8, 0x10, 0], vec![0x42, 3]),
+            (vec![Commands::ReadBuffer as u8, 0x12, 0], vec![1, 2, 3]),
+        ];
+
+        assert_eq!(radio.check_receive(false), Ok(true));
+
+        let mut buff = [0u8; 8];
+        let (header, len, _info) = radio.get_received_ble(&mut buff).unwrap();
+
+        assert_eq!(header.pdu_type(), 0x02);
+        assert!(header.tx_add());
+        assert!(!header.rx_add());
+        assert_eq!(header.length, 3);
+        assert_eq!(&buff[..len], &[1, 2, 3]);
+
+        // Corrupt lengths saturate rather than overflowing with the PDU header
+        radio.hal.reads = vec![(vec![Commands::GetRxBufferStatus as u8, 0], vec![0xFF, 0])];
+        assert_eq!(
+            radio.get_rx_buffer_status(),
+            Ok(RxBufferStatus { ptr: 0, len: 0xFF })
+        );
+    }
+
+    #[test]
+    fn packet_info_snr_raw() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.packet_type = PacketType::LoRa;
+
+        // -6.25 dB SNR (-25 quarter-dB steps)
+        radio.hal.reads = vec![(
+            vec![Commands::GetPacketStatus as u8, 0],
+            vec![0x50, (-25i8) as u8, 0, 0, 0],
+        )];
+
+        let mut info = PacketInfo::default();
+        radio.get_packet_info(&mut info).unwrap();
+
+        assert_eq!(info.rssi, -40);
+        assert_eq!(info.snr_raw, Some(-25));
+        assert_eq!(info.snr, Some(-6));
+    }
+
+    #[test]
+    fn rearm_receive() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.hal.dio = true;
+        radio.config.rf_timeout = Timeout::Configurable {
+            step: TickSize::TickSize1000us,
+            count: 500,
+        };
+
+        radio.start_receive().unwrap();
+        let full = radio.hal.written(&[Commands::SetRx as u8]);
+
+        // Restart on CRC error, re-entering RX without reconfiguring the modem
+        radio.hal.writes.clear();
+        radio.hal.reads = vec![(
+            vec![Commands::GetIrqStatus as u8, 0],
+            vec![0x00, Irq::CRC_ERROR.bits() as u8],
+        )];
+
+        assert_eq!(radio.check_receive(true), Ok(false));
+        assert_eq!(radio.hal.written(&[Commands::SetRx as u8]), full);
+        assert!(radio
+            .hal
+            .written(&[Commands::SetPacketParams as u8])
+            .is_empty());
+    }
+
+    #[test]
+    fn deliver_crc_errors() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.hal.dio = true;
+        radio.packet_type = PacketType::LoRa;
+        radio.config.deliver_crc_errors = true;
+
+        radio.hal.reads = vec![
+            (
+                vec![Commands::GetIrqStatus as u8, 0],
+                vec![0x00, (Irq::RX_DONE | Irq::CRC_ERROR).bits() as u8],
+            ),
+            (vec![Commands::GetRxBufferStatus as u8, 0], vec![2, 0]),
+            (vec![Commands::ReadBuffer as u8, 0, 0], vec![0xAB, 0xCD]),
+        ];
+
+        assert_eq!(radio.check_receive(false), Ok(true));
+
+        let mut buff = [0u8; 4];
+        let (len, info) = radio.get_received(&mut buff).unwrap();
+        assert_eq!(&buff[..len], &[0xAB, 0xCD]);
+        assert!(!info.crc_ok);
+
+        // Errors are returned by default
+        radio.config.deliver_crc_errors = false;
+        assert_eq!(radio.check_receive(false), Err(Error::InvalidCrc));
+    }
+
+    #[test]
+    fn apply_profile() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio
+            .configure_modem(&Modem::Gfsk(GfskConfig::default()))
+            .unwrap();
+
+        let profile = ProtocolProfile {
+            sync_word: Some(&[0xD3, 0x91, 0xD3, 0x91, 0x00]),
+            crc_seed: Some(0x1D0F),
+            crc_poly: Some(0x1021),
+            whitening_seed: Some(0x5A),
+        };
+        radio.apply_profile(&profile).unwrap();
+
+        let reg = |r: Registers| {
+            let r = r as u16;
+            vec![Commands::WiteRegister as u8, (r >> 8) as u8, r as u8]
+        };
+        assert_eq!(
+            radio.hal.written(&reg(Registers::LrSyncWordBaseAddress1)),
+            vec![vec![0xD3, 0x91, 0xD3, 0x91, 0x00]]
+        );
+        assert_eq!(
+            radio.hal.written(&reg(Registers::LrCrcSeedBaseAddr)),
+            vec![vec![0x1D, 0x0F]]
+        );
+        assert_eq!(
+            radio.hal.written(&reg(Registers::LrCrcPolyBaseAddr)),
+            vec![vec![0x10, 0x21]]
+        );
+        assert_eq!(
+            radio.hal.written(&reg(Registers::LrWhitSeedBaseAddr)),
+            vec![vec![0x5A]]
+        );
+
+        // Read back reflects the device registers, not the values written
+        let read = |r: Registers| {
+            let r = r as u16;
+            vec![Commands::ReadRegister as u8, (r >> 8) as u8, r as u8, 0]
+        };
+        radio.hal.reads = vec![
+            (
+                read(Registers::LrSyncWordBaseAddress1),
+                vec![0x12, 0xAD, 0x34, 0xCD, 0x56],
+            ),
+            (read(Registers::LrCrcSeedBaseAddr), vec![0xFF, 0xFF]),
+            (read(Registers::LrCrcPolyBaseAddr), vec![0x80, 0x05]),
+            (read(Registers::LrWhitSeedBaseAddr), vec![0x01]),
+        ];
+        let mut sync_word = [0u8; 5];
+        let device = radio.read_profile(&mut sync_word).unwrap();
+        assert_eq!(
+            device,
+            ProtocolProfile {
+                sync_word: Some(&[0x12, 0xAD, 0x34, 0xCD, 0x56]),
+                crc_seed: Some(0xFFFF),
+                crc_poly: Some(0x8005),
+                whitening_seed: Some(0x01),
+            }
+        );
+
+        // Whitening seed is re-applied after packet configuration
+        radio.hal.writes.clear();
+        radio
+            .configure_modem(&Modem::Gfsk(GfskConfig::default()))
+            .unwrap();
+
+        let prefixes: Vec<_> = radio.hal.writes.iter().map(|(p, _)| p.clone()).collect();
+        let params = prefixes
+            .iter()
+            .position(|p| p == &[Commands::SetPacketParams as u8])
+            .unwrap();
+        let whitening = reg(Registers::LrWhitSeedBaseAddr);
+        assert!(prefixes[params..].iter().any(|p| p == &whitening));
+        assert_eq!(radio.hal.written(&whitening).last(), Some(&vec![0x5A]));
+    }
+
+    #[test]
+    fn get_received_once() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.hal.dio = true;
+        radio.packet_type = PacketType::LoRa;
+
+        radio.hal.reads = vec![
+            (
+                vec![Commands::GetIrqStatus as u8, 0],
+                vec![0x00, Irq::RX_DONE.bits() as u8],
+            ),
+            (vec![Commands::GetRxBufferStatus as u8, 0], vec![2, 0]),
+        ];
+
+        assert_eq!(radio.check_receive(false), Ok(true));
+
+        let mut buff = [0u8; 4];
+        assert!(radio.get_received(&mut buff).is_ok());
+        assert_eq!(radio.get_received(&mut buff), Err(Error::Aborted));
+    }
+
+    #[test]
+    fn radio_status() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        // RX state with data available, LoRa packet type
+        radio.hal.reads = vec![
+            (vec![Commands::GetStatus as u8, 0], vec![0b1010_1000]),
+            (vec![Commands::GetPacketType as u8, 0], vec![0x01]),
+        ];
+
+        let status = radio.status().unwrap();
+        assert_eq!(status.state, State::Rx);
+        assert_eq!(status.command_status, CommandStatus::DataAvailable);
+        assert_eq!(status.packet_type, PacketType::LoRa);
+        assert_eq!(
+            std::format!("{}", status),
+            "Rx (DataAvailable) LoRa @ 2440000000 Hz"
+        );
+    }
+
+    #[test]
+    fn rx_watchdog() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.hal.dio = true;
+        radio.config.rx_watchdog_polls = Some(3);
+
+        // No IRQs with the radio remaining in RX
+        radio.hal.reads = vec![(
+            vec![Commands::GetStatus as u8, 0],
+            vec![(State::Rx as u8) << 5],
+        )];
+
+        for _ in 0..2 {
+            assert_eq!(radio.check_receive(false), Ok(false));
+        }
+        assert!(radio.hal.written(&[Commands::SetRx as u8]).is_empty());
+
+        assert_eq!(radio.check_receive(false), Ok(false));
+        assert_eq!(radio.hal.written(&[Commands::SetStandby as u8]).len(), 1);
+        assert_eq!(radio.hal.written(&[Commands::SetRx as u8]).len(), 1);
+    }
+
+    #[test]
+    fn configure_flrc_sync() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.config.modem = Modem::Flrc(FlrcConfig::default());
+
+        assert_eq!(
+            radio.configure_flrc_sync(
+                &[0x8C, 0x32, 0x01, 0x02],
+                SyncWordRxMatch::RADIO_RX_MATCH_SYNCWORD_1
+            ),
+            Err(Error::InvalidConfiguration)
+        );
+        assert!(radio.hal.writes.is_empty());
+
+        radio
+            .configure_flrc_sync(
+                &[0x01, 0x23, 0x45, 0x67],
+                SyncWordRxMatch::RADIO_RX_MATCH_SYNCWORD_1_2,
+            )
+            .unwrap();
+
+        let params = radio.hal.written(&[Commands::SetPacketParams as u8]);
+        assert_eq!(
+            params.last().unwrap()[2],
+            SyncWordRxMatch::RADIO_RX_MATCH_SYNCWORD_1_2 as u8
+        );
+
+        let mut word = [0u8; 4];
+        radio
+            .hal
+            .read_regs(Registers::LrSyncWordBaseAddress1 as u16 + 1, &mut word)
+            .unwrap();
+        assert_eq!(word, [0x01, 0x23, 0x45, 0x67]);
+    }
+
+    #[test]
+    fn set_fsk_bitrate() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.config.channel = Channel::Gfsk(GfskChannel::default());
+
+        // Packet type must be active
+        assert_eq!(
+            radio.set_fsk_bitrate(GfskBleBitrateBandwidth::BR_0_125_BW_0_3),
+            Err(Error::InvalidConfiguration)
+        );
+
+        radio.packet_type = PacketType::Gfsk;
+        radio
+            .set_fsk_bitrate(GfskBleBitrateBandwidth::BR_0_125_BW_0_3)
+            .unwrap();
+
+        let params = radio.hal.written(&[Commands::SetModulationParams as u8]);
+        assert_eq!(params.len(), 1);
+        assert_eq!(params[0][0], GfskBleBitrateBandwidth::BR_0_125_BW_0_3 as u8);
+
+        assert_eq!(
+            radio.set_flrc_bitrate(FlrcBitrate::BR_0_520_BW_0_6),
+            Err(Error::InvalidConfiguration)
+        );
+    }
+
+    #[test]
+    fn receive_loop() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.hal.dio = true;
+        radio.packet_type = PacketType::LoRa;
+
+        radio.hal.reads = vec![
+            (
+                vec![Commands::GetIrqStatus as u8, 0],
+                vec![0x00, Irq::RX_DONE.bits() as u8],
+            ),
+            (vec![Commands::GetRxBufferStatus as u8, 0], vec![2, 0]),
+            (vec![Commands::ReadBuffer as u8, 0, 0], vec![0x12, 0x34]),
+        ];
+
+        let mut buff = [0u8; 16];
+        let mut n = 0;
+        radio
+            .receive_loop(&mut buff, 100, |data, _info| {
+                assert_eq!(data, &[0x12, 0x34]);
+                n += 1;
+                match n {
+                    3 => LoopControl::Stop,
+                    _ => LoopControl::Continue,
+                }
+            })
+            .unwrap();
+
+        assert_eq!(n, 3);
+        // Initial start plus a re-arm following each continued packet
+        assert_eq!(radio.hal.written(&[Commands::SetRx as u8]).len(), 3);
+    }
+
+    #[test]
+    fn receive_loop_packet_status_error() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.hal.dio = true;
+        radio.config = Config::gfsk();
+
+        radio.hal.reads = vec![
+            (
+                vec![Commands::GetIrqStatus as u8, 0],
+                vec![0x00, Irq::RX_DONE.bits() as u8],
+            ),
+            (vec![Commands::GetRxBufferStatus as u8, 0], vec![2, 0]),
+            (vec![Commands::ReadBuffer as u8, 0, 0], vec![0xBA, 0xD0]),
+            (vec![Commands::ReadBuffer as u8, 0, 0], vec![0x12, 0x34]),
+            (
+                vec![Commands::GetPacketStatus as u8, 0],
+                vec![0, 0, PacketStatus::CRC_ERROR.bits(), 0, 0],
+            ),
+            (
+                vec![Commands::GetPacketStatus as u8, 0],
+                vec![0, 0, PacketStatus::PACKET_RECEIVED.bits(), 0, 0],
+            ),
+        ];
+
+        // The CRC error packet is dropped and receive re-armed for the next
+        let mut buff = [0u8; 16];
+        let mut n = 0;
+        radio
+            .receive_loop(&mut buff, 100, |data, info| {
+                assert_eq!(data, &[0x12, 0x34]);
+                assert!(info.crc_ok);
+                n += 1;
+                LoopControl::Stop
+            })
+            .unwrap();
+
+        assert_eq!(n, 1);
+        assert_eq!(radio.hal.written(&[Commands::SetRx as u8]).len(), 2);
+    }
+
+    #[test]
+    fn poll_transmit() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.packet_type = PacketType::LoRa;
+        radio.hal.dio = true;
+
+        let irq = |radio: &mut Sx128x<MockHal>, irq: Irq| {
+            radio.hal.reads = vec![(
+                vec![Commands::GetIrqStatus as u8, 0],
+                vec![(irq.bits() >> 8) as u8, irq.bits() as u8],
+            )];
+        };
+
+        irq(&mut radio, Irq::empty());
+        assert_eq!(radio.poll_transmit(), Ok(TransmitStatus::InProgress));
+        assert_eq!(radio.check_transmit(), Ok(false));
+
+        irq(&mut radio, Irq::TX_DONE);
+        assert_eq!(radio.poll_transmit(), Ok(TransmitStatus::Done));
+        assert_eq!(radio.check_transmit(), Ok(true));
+
+        irq(&mut radio, Irq::RX_TX_TIMEOUT);
+        assert_eq!(radio.poll_transmit(), Ok(TransmitStatus::Timeout));
+        assert_eq!(radio.check_transmit(), Err(Error::Timeout));
+    }
+
+    #[test]
+    fn lora_sync_word() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        let modem = Modem::LoRa(LoRaConfig {
+            sync_word: Some(LoRaSyncWord::Private),
+            ..Default::default()
+        });
+        radio.configure_modem(&modem).unwrap();
+
+        let mut d = [0u8; 2];
+        radio
+            .hal
+            .read_regs(Registers::LrLoRaSyncWordMsb as u16, &mut d)
+            .unwrap();
+        assert_eq!(d, [0x10, 0x20]);
+
+        assert_eq!(LoRaSyncWord::Public.value(), 0x34);
+        assert_eq!(LoRaSyncWord::Custom(0xAB).value(), 0xAB);
+    }
+
+    #[test]
+    fn new_no_comms() {
+        let version = |v: [u8; 2]| {
+            let reg = Registers::LrFirmwareVersionMsb as u16;
+            let hal = MockHal {
+                reads: vec![(
+                    vec![Commands::ReadRegister as u8, (reg >> 8) as u8, reg as u8, 0],
+                    v.to_vec(),
+                )],
+                ..Default::default()
+            };
+            Sx128x::new(hal, &Config::default()).err()
+        };
+
+        assert_eq!(version([0xFF, 0xFF]), Some(Error::MisoStuckHigh));
+        assert_eq!(version([0x00, 0x00]), Some(Error::MisoStuckLow));
+    }
+
+    #[test]
+    fn probe() {
+        let reg = Registers::LrFirmwareVersionMsb as u16;
+        let mut hal = MockHal {
+            reads: vec![(
+                vec![Commands::ReadRegister as u8, (reg >> 8) as u8, reg as u8, 0],
+                vec![0x12, 0x34],
+            )],
+            ..Default::default()
+        };
+
+        // Returns any responding version, without configuring the device
+        assert_eq!(Sx128x::probe(&mut hal), Ok(0x1234));
+        assert!(hal.writes.is_empty());
+
+        let mut hal = MockHal::default();
+        assert_eq!(Sx128x::probe(&mut hal), Err(Error::MisoStuckLow));
+
+        // Devices not becoming ready following reset time out
+        let mut hal = MockHal {
+            busy: true,
+            ..Default::default()
+        };
+        assert_eq!(Sx128x::probe(&mut hal), Err(Error::BusyTimeout));
+
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.reset_and_wait_ready().unwrap();
+        radio.hal.busy = true;
+        assert_eq!(radio.reset_and_wait_ready(), Err(Error::BusyTimeout));
+    }
+
+    #[test]
+    fn new_invalid_device() {
+        let new = |v: [u8; 2], config: &Config| {
+            let reg = Registers::LrFirmwareVersionMsb as u16;
+            let hal = MockHal {
+                reads: vec![(
+                    vec![Commands::ReadRegister as u8, (reg >> 8) as u8, reg as u8, 0],
+                    v.to_vec(),
+                )],
+                ..Default::default()
+            };
+            Sx128x::new(hal, config).err()
+        };
+
+        let config = Config::default();
+        assert_eq!(new([0xA9, 0xB5], &config), None);
+        assert_eq!(new([0x8E, 0x8E], &config), None);
+        assert_eq!(
+            new([0x12, 0x34], &config),
+            Some(Error::InvalidDevice(0x1234))
+        );
+
+        let config = Config {
+            skip_version_check: true,
+            ..Default::default()
+        };
+        assert_eq!(new([0x12, 0x34], &config), None);
+
+        let config = Config {
+            firmware_versions: &[0x1234],
+            ..Default::default()
+        };
+        assert_eq!(new([0x12, 0x34], &config), None);
+        assert_eq!(
+            new([0xA9, 0xB5], &config),
+            Some(Error::InvalidDevice(0xA9B5))
+        );
+    }
+
+    #[test]
+    fn beacon() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        assert_eq!(radio.beacon(), Err(Error::InvalidConfiguration));
+        assert_eq!(
+            radio.start_beacon(&[0xBE, 0xAC], Timeout::Single),
+            Err(Error::InvalidConfiguration)
+        );
+
+        let interval = Timeout::Configurable {
+            step: TickSize::TickSize4000us,
+            count: 250,
+        };
+        radio.start_beacon(&[0xBE, 0xAC], interval.clone()).unwrap();
+        assert_eq!(radio.beacon_interval(), Some(&interval));
+
+        // Subsequent beacons only re-issue SetTx
+        radio.hal.writes.clear();
+        radio.beacon().unwrap();
+        assert_eq!(
+            radio.hal.writes,
+            vec![(vec![Commands::SetTx as u8], vec![0, 0, 0])]
+        );
+    }
+
+    #[test]
+    fn start_wor() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        let t = |step, count| Timeout::Configurable { step, count };
+
+        assert_eq!(
+            radio.start_wor(
+                t(TickSize::TickSize1000us, 2),
+                t(TickSize::TickSize4000us, 25),
+                64
+            ),
+            Err(Error::InvalidConfiguration)
+        );
+
+        radio
+            .start_wor(
+                t(TickSize::TickSize1000us, 2),
+                t(TickSize::TickSize1000us, 100),
+                512,
+            )
+            .unwrap();
+
+        assert_eq!(
+            radio.hal.written(&[Commands::SetLongPreamble as u8]),
+            vec![vec![1]]
+        );
+        assert_eq!(
+            radio.hal.written(&[Commands::SetRxDutyCycle as u8]),
+            vec![vec![TickSize::TickSize1000us as u8, 0, 2, 0, 100]]
+        );
+
+        // The configured long preamble mode is retained and restored after WOR
+        assert!(!radio.config.long_preamble);
+        radio.hal.writes.clear();
+        radio.start_receive().unwrap();
+        assert_eq!(
+            radio.hal.written(&[Commands::SetLongPreamble as u8]),
+            vec![vec![0]]
+        );
+
+        radio.hal.writes.clear();
+        radio.start_receive().unwrap();
+        assert!(radio
+            .hal
+            .written(&[Commands::SetLongPreamble as u8])
+            .is_empty());
+    }
+
+    #[test]
+    fn reset_irq_masks() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        radio.reset_irq_masks().unwrap();
+
+        let irqs = (Irq::TX_DEFAULT | Irq::RX_DEFAULT).bits();
+        assert_eq!(
+            radio.hal.written(&[Commands::SetDioIrqParams as u8]),
+            vec![vec![
+                (irqs >> 8) as u8,
+                irqs as u8,
+                (irqs >> 8) as u8,
+                irqs as u8,
+                0,
+                0,
+                0,
+                0
+            ]]
+        );
+    }
+
+    #[test]
+    fn packet_info_header_type() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.hal.dio = true;
+        radio.packet_type = PacketType::LoRa;
+
+        let rx = |radio: &mut Sx128x<MockHal>, irq: Irq| {
+            radio.hal.reads = vec![
+                (
+                    vec![Commands::GetIrqStatus as u8, 0],
+                    vec![(irq.bits() >> 8) as u8, irq.bits() as u8],
+                ),
+                (vec![Commands::GetRxBufferStatus as u8, 0], vec![1, 0]),
+            ];
+            assert_eq!(radio.check_receive(false), Ok(true));
+
+            let mut buff = [0u8; 4];
+            radio.get_received(&mut buff).unwrap().1.header_type
+        };
+
+        // Explicit header reception raises the header valid IRQ
+        radio.config.modem = Modem::LoRa(LoRaConfig {
+            header_type: LoRaHeader::Implicit,
+            ..Default::default()
+        });
+        assert_eq!(
+            rx(&mut radio, Irq::RX_DONE | Irq::HEADER_VALID),
+            Some(LoRaHeader::Explicit)
+        );
+
+        // Otherwise fall back to the configured header mode
+        assert_eq!(rx(&mut radio, Irq::RX_DONE), Some(LoRaHeader::Implicit));
+    }
+
+    #[test]
+    fn ranging_fs() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        let config = Config {
+            modem: Modem::Ranging(LoRaConfig::default()),
+            channel: Channel::Ranging(LoRaChannel::default()),
+            ranging_fs: true,
+            ..Default::default()
+        };
+        radio.configure(&config).unwrap();
+
+        assert_eq!(
+            radio.hal.written(&[Commands::SetAutoFs as u8]),
+            vec![vec![1]]
+        );
+
+        // Ranging exchanges configure from FS rather than standby
+        radio.hal.writes.clear();
+        radio.start_transmit(&[0x01]).unwrap();
+        radio.start_receive().unwrap();
+
+        assert_eq!(radio.hal.written(&[Commands::SetFs as u8]).len(), 2);
+        assert!(radio.hal.written(&[Commands::SetStandby as u8]).is_empty());
+    }
+
+    #[test]
+    fn valid_spreading_factors() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        for bw in [
+            LoRaBandwidth::Bw200kHz,
+            LoRaBandwidth::Bw400kHz,
+            LoRaBandwidth::Bw800kHz,
+            LoRaBandwidth::Bw1600kHz,
+        ] {
+            for sf in bw.valid_spreading_factors() {
+                let ch = Channel::LoRa(LoRaChannel {
+                    sf: *sf,
+                    bw,
+                    ..Default::default()
+                });
+                assert_eq!(radio.set_channel(&ch), Ok(()));
+            }
+        }
+    }
+
+    #[test]
+    fn peek_header() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.packet_type = PacketType::LoRa;
+
+        assert_eq!(radio.peek_header(), Ok(None));
+
+        let reg = |r: Registers| {
+            let r = r as u16;
+            vec![Commands::ReadRegister as u8, (r >> 8) as u8, r as u8, 0]
+        };
+        radio.hal.reads = vec![
+            (
+                vec![Commands::GetIrqStatus as u8, 0],
+                vec![0x00, Irq::HEADER_VALID.bits() as u8],
+            ),
+            (vec![Commands::GetRxBufferStatus as u8, 0], vec![42, 0]),
+            (reg(Registers::LrIncomingCodingRate), vec![0x40]),
+        ];
+
+        // CRC info shares a register with the frequency error MSB (bits 3:0)
+        let crc_info = (
+            vec![
+                Commands::ReadRegister as u8,
+                (REG_LR_INCOMING_CRC_INFO >> 8) as u8,
+                REG_LR_INCOMING_CRC_INFO as u8,
+                0,
+            ],
+            vec![0x0F],
+        );
+        radio.hal.reads.push(crc_info.clone());
+        assert_eq!(
+            radio.peek_header(),
+            Ok(Some(HeaderInfo {
+                length: 42,
+                coding_rate: Some(LoRaCodingRate::Cr4_8),
+                crc: false,
+            }))
+        );
+
+        radio.hal.reads.pop();
+        radio.hal.reads.push((crc_info.0, vec![0x13]));
+        assert_eq!(
+            radio.peek_header(),
+            Ok(Some(HeaderInfo {
+                length: 42,
+                coding_rate: Some(LoRaCodingRate::Cr4_8),
+                crc: true,
+            }))
+        );
+    }
+
+    #[test]
+    fn circuit_state_recovery() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.config.circuit_state_recovery = Some(CircuitStateRecovery {
+            threshold: 2,
+            window: 3,
+        });
+
+        let status = |radio: &mut Sx128x<MockHal>, raw: u8| {
+            radio.hal.reads = vec![(vec![Commands::GetStatus as u8, 0], vec![raw])];
+            radio.get_status().map(|s| s.0)
+        };
+        let recoveries =
+            |radio: &Sx128x<MockHal>| radio.hal.written(&[Commands::SetRegulatorMode as u8]).len();
+
+        // Invalid states outside of the window are tolerated
+        assert_eq!(
+            status(&mut radio, 0x20),
+            Err(Error::InvalidCircuitState(0x20))
+        );
+        assert_eq!(status(&mut radio, 0x40), Ok(State::StandbyRc));
+        assert_eq!(status(&mut radio, 0x40), Ok(State::StandbyRc));
+        assert_eq!(
+            status(&mut radio, 0x20),
+            Err(Error::InvalidCircuitState(0x20))
+        );
+        assert!(!radio.recovery_pending());
+        assert_eq!(radio.recover(), Ok(false));
+
+        // Reaching the threshold within the window flags recovery, without
+        // resetting from within the status read
+        assert_eq!(
+            status(&mut radio, 0x20),
+            Err(Error::InvalidCircuitState(0x20))
+        );
+        assert!(radio.recovery_pending());
+        assert_eq!(recoveries(&radio), 0);
+
+        // Recovery resets and restores the device once requested
+        radio.hal.reads.clear();
+        assert_eq!(radio.recover(), Ok(true));
+        assert!(!radio.recovery_pending());
+        assert_eq!(recoveries(&radio), 1);
+    }
+
+    #[test]
+    fn transceive() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.packet_type = PacketType::LoRa;
+        radio.hal.dio = true;
+
+        let timeout = Timeout::Configurable {
+            step: TickSize::TickSize1000us,
+            count: 10,
+        };
+        let irq = (Irq::TX_DONE | Irq::RX_DONE).bits();
+        radio.hal.reads = vec![
+            (
+                vec![Commands::GetIrqStatus as u8, 0],
+                vec![(irq >> 8) as u8, irq as u8],
+            ),
+            (vec![Commands::GetRxBufferStatus as u8, 0], vec![2, 0]),
+            (vec![Commands::ReadBuffer as u8, 0, 0], vec![0xBE, 0xEF]),
+        ];
+
+        let mut buff = [0u8; 4];
+        let (n, _info) = radio
+            .transceive(&[0x01, 0x02], &mut buff, timeout.clone())
+            .unwrap();
+        assert_eq!(&buff[..n], &[0xBE, 0xEF]);
+
+        // Receive is armed with the provided timeout following transmission
+        assert_eq!(
+            radio.hal.written(&[Commands::SetRx as u8]),
+            vec![vec![timeout.step() as u8, 0, 10]]
+        );
+
+        // Continuous receive is bounded by the configured timeout
+        let irq = Irq::TX_DONE.bits();
+        radio.hal.reads = vec![(
+            vec![Commands::GetIrqStatus as u8, 0],
+            vec![(irq >> 8) as u8, irq as u8],
+        )];
+        radio.config.timeout_ms = 5;
+        assert_eq!(
+            radio
+                .transceive(&[0x01, 0x02], &mut buff, Timeout::Continuous)
+                .map(|(n, _)| n),
+            Err(Error::Timeout)
+        );
+    }
+
+    #[test]
+    fn cad() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        radio.packet_type = PacketType::Gfsk;
+        assert_eq!(radio.start_cad(), Err(Error::InvalidConfiguration));
+
+        radio.packet_type = PacketType::LoRa;
+        radio.config.cad_params.symbols = CadSymbols::Symbols8;
+        radio.start_cad().unwrap();
+
+        assert_eq!(
+            radio.hal.written(&[Commands::SetCadParams as u8]),
+            vec![vec![0x60]]
+        );
+        assert_eq!(radio.hal.written(&[Commands::SetCad as u8]), vec![vec![]]);
+
+        let cad = |radio: &mut Sx128x<MockHal>, irq: Irq| {
+            radio.hal.reads = vec![(
+                vec![Commands::GetIrqStatus as u8, 0],
+                vec![(irq.bits() >> 8) as u8, irq.bits() as u8],
+            )];
+            radio.check_cad()
+        };
+
+        assert_eq!(cad(&mut radio, Irq::empty()), Ok(None));
+        assert_eq!(cad(&mut radio, Irq::CAD_DONE), Ok(Some(false)));
+        assert_eq!(
+            cad(&mut radio, Irq::CAD_DONE | Irq::CAD_ACTIVITY_DETECTED),
+            Ok(Some(true))
+        );
+        assert!(radio.hal.written(&[Commands::SetRx as u8]).is_empty());
+
+        // Receive exit mode starts RX on activity
+        radio.config.cad_params.exit_mode = CadExitMode::Receive;
+        assert_eq!(
+            cad(&mut radio, Irq::CAD_DONE | Irq::CAD_ACTIVITY_DETECTED),
+            Ok(Some(true))
+        );
+        assert_eq!(radio.hal.written(&[Commands::SetRx as u8]).len(), 1);
+    }
+
+    #[test]
+    fn is_channel_clear() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.packet_type = PacketType::LoRa;
+        radio.config.cad_params.exit_mode = CadExitMode::Receive;
+        radio.config.timeout_ms = 5;
+
+        let reads = |state: u8, irq: Irq| {
+            vec![
+                (vec![Commands::GetStatus as u8, 0], vec![state << 5 | 0x04]),
+                (
+                    vec![Commands::GetIrqStatus as u8, 0],
+                    vec![(irq.bits() >> 8) as u8, irq.bits() as u8],
+                ),
+            ]
+        };
+
+        // Clear channel from standby, remaining in standby
+        radio.hal.reads = reads(State::StandbyRc as u8, Irq::CAD_DONE);
+        assert_eq!(radio.is_channel_clear(CadSymbols::Symbols16), Ok(true));
+        assert_eq!(
+            radio.hal.written(&[Commands::SetCadParams as u8]),
+            vec![vec![CadSymbols::Symbols16 as u8]]
+        );
+        assert!(radio.hal.written(&[Commands::SetRx as u8]).is_empty());
+        assert_eq!(radio.config.cad_params.exit_mode, CadExitMode::Receive);
+
+        // Busy channel from RX, restoring RX
+        radio.hal.reads = reads(State::Rx as u8, Irq::CAD_DONE | Irq::CAD_ACTIVITY_DETECTED);
+        assert_eq!(radio.is_channel_clear(CadSymbols::Symbols16), Ok(false));
+        assert_eq!(radio.hal.written(&[Commands::SetRx as u8]).len(), 1);
+
+        // Missing CAD completion times out
+        radio.hal.reads = reads(State::StandbyRc as u8, Irq::empty());
+        assert_eq!(
+            radio.is_channel_clear(CadSymbols::Symbols16),
+            Err(Error::Timeout)
+        );
+
+        radio.hal.reads = reads(State::Tx as u8, Irq::empty());
+        assert_eq!(
+            radio.is_channel_clear(CadSymbols::Symbols16),
+            Err(Error::InvalidState(State::StandbyRc, State::Tx))
+        );
+    }
+
+    #[test]
+    fn get_frequency_error() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        radio.packet_type = PacketType::Flrc;
+        assert_eq!(
+            radio.get_frequency_error(),
+            Err(Error::InvalidConfiguration)
+        );
+
+        radio.packet_type = PacketType::LoRa;
+        radio.config.channel = Channel::LoRa(LoRaChannel {
+            bw: LoRaBandwidth::Bw1600kHz,
+            ..Default::default()
+        });
+
+        let reg = Registers::LrEstimatedFrequencyErrorMsb as u16;
+        let prefix = vec![Commands::ReadRegister as u8, (reg >> 8) as u8, reg as u8, 0];
+
+        // Upper nibble is masked
+        radio.hal.reads = vec![(prefix.clone(), vec![0xF0, 0x03, 0xE8])];
+        assert_eq!(radio.get_frequency_error(), Ok(1574));
+
+        // Negative errors are sign-extended from 20 bits
+        radio.hal.reads = vec![(prefix, vec![0x0F, 0xFC, 0x18])];
+        assert_eq!(radio.get_frequency_error(), Ok(-1574));
+    }
+
+    #[test]
+    fn ranging_result() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        radio.packet_type = PacketType::LoRa;
+        assert_eq!(
+            radio.get_ranging_result(RangingResultType::Raw),
+            Err(Error::InvalidConfiguration)
+        );
+
+        radio.packet_type = PacketType::Ranging;
+        radio.config.channel = Channel::Ranging(LoRaChannel {
+            bw: LoRaBandwidth::Bw1600kHz,
+            ..Default::default()
+        });
+
+        radio.set_ranging_addresses(0x12345678, 0x9ABCDEF0).unwrap();
+        let reg = |r: Registers| {
+            let r = r as u16;
+            vec![Commands::WiteRegister as u8, (r >> 8) as u8, r as u8]
+        };
+        assert_eq!(
+            radio.hal.written(&reg(Registers::LrDeviceRangingAddr)),
+            vec![vec![0x12, 0x34, 0x56, 0x78]]
+        );
+        assert_eq!(
+            radio.hal.written(&reg(Registers::LrRequestRangingAddr)),
+            vec![vec![0x9A, 0xBC, 0xDE, 0xF0]]
+        );
+
+        let result = Registers::LrRangingResultBaseAddr as u16;
+        radio.hal.reads = vec![(
+            vec![
+                Commands::ReadRegister as u8,
+                (result >> 8) as u8,
+                result as u8,
+                0,
+            ],
+            vec![0x00, 0x01, 0x00],
+        )];
+
+        let d = radio
+            .get_ranging_result(RangingResultType::Averaged)
+            .unwrap();
+        assert!((d - 51.2).abs() < 0.01);
+        assert_eq!(
+            radio.hal.written(&reg(Registers::LrRangingResultConfig)),
+            vec![vec![0x10]]
+        );
+
+        // Results are unfrozen following the read
+        assert_eq!(
+            radio.hal.written(&reg(Registers::LrRangingResultsFreeze)),
+            vec![vec![0x02], vec![0x00]]
+        );
+    }
+
+    #[test]
+    fn start_rx_duty_cycle() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        let t = |step, count| Timeout::Configurable { step, count };
+
+        assert_eq!(
+            radio.start_rx_duty_cycle(Timeout::Single, t(TickSize::TickSize1000us, 100)),
+            Err(Error::InvalidConfiguration)
+        );
+        assert_eq!(
+            radio.start_rx_duty_cycle(
+                t(TickSize::TickSize0015us, 64),
+                t(TickSize::TickSize1000us, 100)
+            ),
+            Err(Error::InvalidConfiguration)
+        );
+
+        radio
+            .start_rx_duty_cycle(
+                t(TickSize::TickSize1000us, 5),
+                t(TickSize::TickSize1000us, 0x0120),
+            )
+            .unwrap();
+        radio
+            .start_rx_duty_cycle(Timeout::Continuous, t(TickSize::TickSize4000us, 250))
+            .unwrap();
+
+        assert_eq!(
+            radio.hal.written(&[Commands::SetRxDutyCycle as u8]),
+            vec![
+                vec![TickSize::TickSize1000us as u8, 0x00, 0x05, 0x01, 0x20],
+                vec![TickSize::TickSize4000us as u8, 0xFF, 0xFF, 0x00, 250],
+            ]
+        );
+    }
+
+    #[test]
+    fn tx_continuous() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        radio.start_tx_continuous_wave().unwrap();
+        radio.start_tx_continuous_preamble().unwrap();
+        radio.stop_continuous().unwrap();
+
+        assert_eq!(
+            radio.hal.written(&[Commands::SetTxContinuousWave as u8]),
+            vec![vec![]]
+        );
+        assert_eq!(
+            radio
+                .hal
+                .written(&[Commands::SetTxContinuousPreamble as u8]),
+            vec![vec![]]
+        );
+        assert_eq!(
+            radio.hal.written(&[Commands::SetRfFrequency as u8]).len(),
+            2
+        );
+        assert_eq!(radio.hal.written(&[Commands::SetTxParams as u8]).len(), 2);
+        assert_eq!(radio.hal.written(&[Commands::SetStandby as u8]).len(), 3);
+    }
+
+    #[test]
+    fn poll_while_busy() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.hal.busy = true;
+
+        // Polling returns immediately without issuing commands while busy
+        assert_eq!(radio.check_receive(false), Ok(false));
+        assert_eq!(radio.poll_transmit(), Ok(TransmitStatus::InProgress));
+        assert!(radio.hal.writes.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "poll-irq")]
+    fn poll_latched_dio() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.packet_type = PacketType::LoRa;
+        radio.hal.reads = vec![
+            (
+                vec![Commands::GetIrqStatus as u8, 0],
+                vec![0, Irq::RX_DONE.bits() as u8],
+            ),
+            (vec![Commands::GetRxBufferStatus as u8, 0], vec![2, 0]),
+        ];
+
+        // Pending IRQs are not read without a DIO edge
+        assert_eq!(radio.check_receive(false), Ok(false));
+
+        // A latched edge is consumed by the poll, even where the DIO level has
+        // since been cleared
+        radio.hal.dio_edge = true;
+        assert_eq!(radio.check_receive(false), Ok(true));
+        assert!(!radio.hal.dio_edge);
+    }
+
+    #[test]
+    fn set_whitening_seed() {
+        let mut radio = Sx128x::build(MockHal::default());
+        let reg = [
+            Commands::WiteRegister as u8,
+            (Registers::LrWhitSeedBaseAddr as u16 >> 8) as u8,
+            Registers::LrWhitSeedBaseAddr as u16 as u8,
+        ];
+
+        // Unsupported in LoRa mode
+        radio.packet_type = PacketType::LoRa;
+        assert_eq!(
+            radio.set_whitening_seed(0x5A),
+            Err(Error::InvalidConfiguration)
+        );
+        assert!(radio.hal.written(&reg).is_empty());
+
+        radio.packet_type = PacketType::Gfsk;
+        radio.set_whitening_seed(0x5A).unwrap();
+        assert_eq!(radio.hal.written(&reg), vec![vec![0x5A]]);
+
+        // Re-applied following modem configuration
+        radio
+            .configure_modem(&Modem::Gfsk(GfskConfig::default()))
+            .unwrap();
+        assert_eq!(radio.hal.written(&reg), vec![vec![0x5A], vec![0x5A]]);
+    }
+
+    #[test]
+    fn set_preamble_length() {
+        let mut radio = Sx128x::build(MockHal::default());
+        let reg = [
+            Commands::WiteRegister as u8,
+            (Registers::GfskBlePreambleLength as u16 >> 8) as u8,
+            Registers::GfskBlePreambleLength as u16 as u8,
+        ];
+
+        // Unsupported in LoRa mode
+        radio.config = Config::lora();
+        assert_eq!(
+            radio.set_preamble_length(16),
+            Err(Error::InvalidConfiguration)
+        );
+
+        radio.config = Config::gfsk();
+        if let Modem::Gfsk(c) = &mut radio.config.modem {
+            c.patch_preamble = true;
+        }
+        for bits in [0, 6, 36] {
+            assert_eq!(
+                radio.set_preamble_length(bits),
+                Err(Error::InvalidConfiguration)
+            );
+        }
+        assert!(radio.hal.writes.is_empty());
+
+        radio.set_preamble_length(12).unwrap();
+        assert_eq!(
+            radio.hal.writes[0],
+            (vec![Commands::SetStandby as u8], vec![0])
+        );
+        match &radio.config.modem {
+            Modem::Gfsk(c) => assert_eq!(c.preamble_length, PreambleLength::PreambleLength12),
+            _ => unreachable!(),
+        }
+        assert_eq!(
+            radio.hal.written(&[Commands::SetPacketParams as u8])[0][0],
+            PreambleLength::PreambleLength12 as u8
+        );
+        assert_eq!(radio.hal.written(&reg), vec![vec![0x20]]);
+    }
+
+    #[test]
+    fn register_access() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        radio
+            .write_register(Registers::LrSyncWordTolerance, 0x12)
+            .unwrap();
+        assert_eq!(
+            radio
+                .hal
+                .written(&[Commands::WiteRegister as u8, 0x09, 0xCD]),
+            vec![vec![0x12]]
+        );
+
+        assert_eq!(
+            radio.read_register(Registers::LrSyncWordTolerance),
+            Ok(0x12)
+        );
+    }
+
+    #[test]
+    fn set_syncword_length() {
+        let mut radio = Sx128x::build(MockHal::default());
+        let modem = Modem::Gfsk(GfskConfig {
+            sync_word_length: GfskSyncWordLength::GFSK_SYNCWORD_LENGTH_3_BYTE,
+            ..Default::default()
+        });
+        radio.configure_modem(&modem).unwrap();
+        radio.config.modem = modem;
+
+        // Mismatched with configured length
+        assert_eq!(
+            radio.set_syncword(1, &[1, 2, 3, 4, 5]),
+            Err(Error::InvalidConfiguration)
+        );
+
+        // Shorter sync words are written to the end of the sync word register
+        radio.set_syncword(1, &[1, 2, 3]).unwrap();
+        let addr = Registers::LrSyncWordBaseAddress1 as u16 + 2;
+        assert_eq!(
+            radio
+                .hal
+                .written(&[Commands::WiteRegister as u8, (addr >> 8) as u8, addr as u8]),
+            vec![vec![1, 2, 3]]
+        );
+    }
+
+    #[test]
+    fn set_ble_access_address() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        // Only valid in BLE mode
+        radio.packet_type = PacketType::Gfsk;
+        assert_eq!(
+            radio.set_ble_access_address(0x8E89BED6),
+            Err(Error::InvalidConfiguration)
+        );
+
+        radio.packet_type = PacketType::Ble;
+        radio.set_ble_access_address(0x8E89BED6).unwrap();
+        let addr = Registers::LrBleAccessAddress as u16;
+        assert_eq!(
+            radio
+                .hal
+                .written(&[Commands::WiteRegister as u8, (addr >> 8) as u8, addr as u8]),
+            vec![vec![0x8E, 0x89, 0xBE, 0xD6]]
+        );
+    }
+
+    #[test]
+    fn packet_status_errors() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.packet_type = PacketType::Gfsk;
+
+        let receive = |radio: &mut Sx128x<MockHal>, s: PacketStatus| {
+            radio.rx_pending = true;
+            radio.hal.reads = vec![
+                (vec![Commands::GetRxBufferStatus as u8, 0], vec![2, 0]),
+                (
+                    vec![Commands::GetPacketStatus as u8, 0],
+                    vec![0, 0, s.bits(), 0, 0],
+                ),
+            ];
+            let mut buff = [0u8; 4];
+            radio.get_received(&mut buff).map(|(_, info)| info)
+        };
+
+        assert!(receive(&mut radio, PacketStatus::PACKET_RECEIVED).is_ok());
+        assert_eq!(
+            receive(&mut radio, PacketStatus::CRC_ERROR),
+            Err(Error::InvalidCrc)
+        );
+        assert_eq!(
+            receive(&mut radio, PacketStatus::SYNC_ERROR),
+            Err(Error::InvalidSync)
+        );
+        assert_eq!(
+            receive(&mut radio, PacketStatus::LENGTH_ERROR),
+            Err(Error::InvalidLength)
+        );
+        assert_eq!(
+            receive(&mut radio, PacketStatus::ABORT_ERROR),
+            Err(Error::Abort)
+        );
+
+        // CRC errors are flagged when delivered
+        radio.config.deliver_crc_errors = true;
+        let info = receive(&mut radio, PacketStatus::CRC_ERROR).unwrap();
+        assert!(!info.crc_ok);
+
+        // Checks may be disabled to inspect bad frames
+        radio.config.ignore_packet_status = true;
+        let info = receive(&mut radio, PacketStatus::ABORT_ERROR).unwrap();
+        assert!(info.packet_status.contains(PacketStatus::ABORT_ERROR));
+    }
+
+    #[test]
+    fn detect_packet_controller_busy() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.packet_type = PacketType::Flrc;
+        radio.hal.dio = true;
+
+        let status = PacketStatus::PACKET_CONTROLER_BUSY.bits();
+        radio.hal.reads = vec![
+            (
+                vec![Commands::GetIrqStatus as u8, 0],
+                vec![
+                    (Irq::RX_TX_TIMEOUT.bits() >> 8) as u8,
+                    Irq::RX_TX_TIMEOUT.bits() as u8,
+                ],
+            ),
+            (
+                vec![Commands::GetPacketStatus as u8, 0],
+                vec![0, 0, status, 0, 0],
+            ),
+        ];
+
+        // Packet status is not checked on timeouts unless enabled
+        assert_eq!(radio.check_receive(false), Err(Error::Timeout));
+        assert_eq!(radio.poll_transmit(), Ok(TransmitStatus::Timeout));
+
+        radio.config.detect_packet_controller_busy = true;
+        assert_eq!(radio.check_receive(false), Err(Error::PacketControllerBusy));
+        assert_eq!(radio.poll_transmit(), Err(Error::PacketControllerBusy));
+    }
+
+    #[test]
+    fn configure_adopts_config() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        let config = Config {
+            rf_timeout: Timeout::Continuous,
+            timeout_ms: 5,
+            skip_standby: true,
+            ..Config::gfsk()
+        };
+        radio.configure(&config).unwrap();
+
+        // All fields are retained, other than the driver-tracked packet type
+        let mut expected = config.clone();
+        expected.packet_type = radio.config.packet_type;
+        assert_eq!(radio.config, expected);
+    }
+
+    #[test]
+    fn lna_boost() {
+        let mut radio = Sx128x::build(MockHal::default());
+        let reg = [
+            Commands::WiteRegister as u8,
+            (Registers::LnaRegime as u16 >> 8) as u8,
+            Registers::LnaRegime as u16 as u8,
+        ];
+
+        radio.set_lna_boost(true).unwrap();
+        assert_eq!(radio.hal.written(&reg), vec![vec![0xC0]]);
+
+        // Re-applied on configuration
+        radio.hal.writes.clear();
+        let config = radio.config.clone();
+        radio.configure(&config).unwrap();
+        assert_eq!(radio.hal.written(&reg), vec![vec![0xC0]]);
+
+        radio.set_lna_boost(false).unwrap();
+        assert!(!radio.config.lna_boost);
+        assert_eq!(radio.hal.written(&reg).last(), Some(&vec![0x00]));
+    }
+
+    #[test]
+    fn manual_gain() {
+        let mut radio = Sx128x::build(MockHal::default());
+        let reg = |r: Registers| {
+            let r = r as u16;
+            vec![Commands::WiteRegister as u8, (r >> 8) as u8, r as u8]
+        };
+
+        radio.set_manual_gain(Some(0x1A)).unwrap();
+
+        let writes: Vec<_> = radio
+            .hal
+            .writes
+            .iter()
+            .map(|(p, d)| (p.clone(), d[0]))
+            .collect();
+        assert_eq!(
+            writes,
+            vec![
+                (reg(Registers::EnableManuaLGainControl), 0x80),
+                (reg(Registers::DemodDetection), 0x00),
+                (reg(Registers::ManualGainValue), 0x0A),
+            ]
+        );
+
+        radio.hal.writes.clear();
+        radio.set_manual_gain(None).unwrap();
+
+        let writes: Vec<_> = radio
+            .hal
+            .writes
+            .iter()
+            .map(|(p, d)| (p.clone(), d[0]))
+            .collect();
+        assert_eq!(
+            writes,
+            vec![
+                (reg(Registers::EnableManuaLGainControl), 0x00),
+                (reg(Registers::DemodDetection), 0x01),
+            ]
+        );
+    }
+
+    #[test]
+    fn get_status() {
+        use radio::State as _;
+
+        let mut radio = Sx128x::build(MockHal::default());
+
+        // StandbyRc (0x2) with command execution failure (0x5)
+        radio.hal.reads = vec![(vec![Commands::GetStatus as u8, 0], vec![0b0101_0100])];
+
+        assert_eq!(
+            radio.get_status(),
+            Ok((State::StandbyRc, CommandStatus::ExecutionFailure))
+        );
+        assert_eq!(radio.get_state(), Ok(State::StandbyRc));
+
+        // Undocumented state 0x07 is reported rather than an error
+        radio.hal.reads = vec![(vec![Commands::GetStatus as u8, 0], vec![0b1110_0100])];
+        assert_eq!(radio.get_state(), Ok(State::Unknown));
+        assert_eq!(
+            radio.set_state(State::Unknown),
+            Err(Error::InvalidStateCommand)
+        );
+    }
+
+    #[test]
+    fn sleep_retain() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        radio
+            .sleep_retain(SleepConfig::RETAIN_DATA_RAM | SleepConfig::RETAIN_DATA_BUFFER)
+            .unwrap();
+        assert_eq!(
+            radio.hal.writes,
+            vec![
+                (vec![Commands::SetSaveContext as u8], vec![]),
+                (vec![Commands::SetSleep as u8], vec![0x03]),
+            ]
+        );
+
+        radio.hal.writes.clear();
+        radio.wake().unwrap();
+        assert_eq!(
+            radio.hal.writes,
+            vec![
+                (vec![Commands::GetStatus as u8, 0], vec![]),
+                (vec![Commands::SetStandby as u8], vec![0]),
+            ]
+        );
+    }
+
+    #[test]
+    fn sleep_config() {
+        use radio::State as _;
+
+        let mut radio = Sx128x::build(MockHal::default());
+
+        radio
+            .sleep(SleepConfig::RETAIN_INSTRUCTION_RAM | SleepConfig::RETAIN_DATA_BUFFER)
+            .unwrap();
+        radio.set_state(State::Sleep).unwrap();
+
+        assert_eq!(
+            radio.hal.written(&[Commands::SetSleep as u8]),
+            vec![vec![0x06], vec![0x00]]
+        );
+    }
+
+    #[test]
+    fn sleep_restores_packet_type() {
+        let mut radio = Sx128x::build(MockHal::default());
+        let config = Config::flrc();
+        radio.configure(&config).unwrap();
+
+        // Configuration is lost in sleep so the packet type must be re-applied
+        radio.sleep(SleepConfig::empty()).unwrap();
+        radio.wake().unwrap();
+        radio.hal.writes.clear();
+        radio.configure(&config).unwrap();
+        assert_eq!(
+            radio.hal.written(&[Commands::SetPacketType as u8]),
+            vec![vec![PacketType::Flrc as u8]]
+        );
+
+        // As is the case following a reset
+        radio.reset().unwrap();
+        radio.hal.writes.clear();
+        radio.configure(&config).unwrap();
+        assert_eq!(
+            radio.hal.written(&[Commands::SetPacketType as u8]),
+            vec![vec![PacketType::Flrc as u8]]
+        );
+    }
+
+    #[test]
+    fn long_preamble() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        radio.set_long_preamble(true).unwrap();
+        assert_eq!(
+            radio.hal.writes,
+            vec![(vec![Commands::SetLongPreamble as u8], vec![0x01])]
+        );
+
+        // Re-applied on configuration
+        radio.hal.writes.clear();
+        let config = radio.config.clone();
+        radio.configure(&config).unwrap();
+        assert_eq!(
+            radio.hal.written(&[Commands::SetLongPreamble as u8]),
+            vec![vec![0x01]]
+        );
+    }
+
+    #[test]
+    fn auto_fs() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        radio.set_auto_fs(true).unwrap();
+        assert_eq!(radio.hal.writes, vec![(vec![0x9E], vec![0x01])]);
+
+        // Re-applied on configuration
+        radio.hal.writes.clear();
+        let config = radio.config.clone();
+        radio.configure(&config).unwrap();
+        assert_eq!(
+            radio.hal.written(&[Commands::SetAutoFs as u8]),
+            vec![vec![0x01]]
+        );
+    }
+
+    #[test]
+    fn set_auto_tx() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        assert_eq!(
+            radio.set_auto_tx(AutoTx::Enabled(AUTO_RX_TX_OFFSET)),
+            Err(Error::InvalidConfiguration)
+        );
+        assert_eq!(
+            radio.set_auto_tx(AutoTx::Enabled(0)),
+            Err(Error::InvalidConfiguration)
+        );
+        assert!(radio.hal.writes.is_empty());
+
+        radio
+            .set_auto_tx(AutoTx::Enabled(AUTO_RX_TX_OFFSET + 0x0100))
+            .unwrap();
+        radio.set_auto_tx(AutoTx::Disabled).unwrap();
+        assert_eq!(
+            radio.hal.written(&[Commands::SetAutoTx as u8]),
+            vec![vec![0x01, 0x00], vec![0x00, 0x00]]
+        );
+    }
+
+    #[test]
+    fn poll_rssi_state() {
+        use radio::Rssi as _;
+
+        let mut radio = Sx128x::build(MockHal::default());
+
+        // StandbyRc
+        radio.hal.reads = vec![
+            (vec![Commands::GetStatus as u8, 0], vec![0b0100_0100]),
+            (vec![Commands::GetRssiInst as u8, 0], vec![180]),
+        ];
+        assert_eq!(
+            radio.poll_rssi(),
+            Err(Error::InvalidState(State::Rx, State::StandbyRc))
+        );
+        assert_eq!(radio.poll_rssi_unchecked(), Ok(-90));
+
+        // Rx
+        radio.hal.reads[0].1 = vec![0b1010_0100];
+        assert_eq!(radio.poll_rssi(), Ok(-90));
+    }
+
+    #[test]
+    fn buffer_base_addr() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.hal.dio = true;
+        let base = [Commands::SetBufferBaseAddress as u8];
+
+        radio.set_buffer_base_addr(0x00, 0x80).unwrap();
+        assert_eq!(radio.hal.written(&base), vec![vec![0x00, 0x80]]);
+
+        // Applied on transmit, with data written at the TX base
+        radio.start_transmit(&[1, 2, 3]).unwrap();
+        assert_eq!(radio.hal.written(&base).last(), Some(&vec![0x00, 0x80]));
+        assert_eq!(
+            radio.hal.written(&[Commands::WriteBuffer as u8, 0x00]),
+            vec![vec![1, 2, 3]]
+        );
+
+        // Applied on receive, with data read from the reported RX pointer
+        radio.start_receive().unwrap();
+        assert_eq!(radio.hal.written(&base).last(), Some(&vec![0x00, 0x80]));
+
+        radio.hal.reads = vec![
+            (
+                vec![Commands::GetIrqStatus as u8, 0],
+                vec![0x00, Irq::RX_DONE.bits() as u8],
+            ),
+            (vec![Commands::GetRxBufferStatus as u8, 0], vec![2, 0x80]),
+            (vec![Commands::ReadBuffer as u8, 0x80, 0], vec![0xAB, 0xCD]),
+        ];
+
+        assert_eq!(radio.check_receive(false), Ok(true));
+        assert_eq!(
+            radio.get_rx_buffer_status(),
+            Ok(RxBufferStatus { ptr: 0x80, len: 2 })
+        );
+
+        let mut buff = [0u8; 4];
+        let (len, _info) = radio.get_received(&mut buff).unwrap();
+        assert_eq!(&buff[..len], &[0xAB, 0xCD]);
+    }
+
+    #[test]
+    fn rx_buffer_status() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.config = Config::gfsk();
+        radio.hal.reads = vec![(vec![Commands::GetRxBufferStatus as u8, 0], vec![5, 0x20])];
+
+        assert_eq!(
+            radio.get_rx_buffer_status(),
+            Ok(RxBufferStatus { ptr: 0x20, len: 5 })
+        );
+
+        // Implicit header length is read from the payload length register
+        radio.config = Config::lora();
+        if let Modem::LoRa(c) = &mut radio.config.modem {
+            c.header_type = LoRaHeader::Implicit;
+        }
+        radio
+            .hal
+            .write_reg(Registers::LrPayloadLength as u16, 9)
+            .unwrap();
+
+        assert_eq!(
+            radio.get_rx_buffer_status(),
+            Ok(RxBufferStatus { ptr: 0x20, len: 9 })
+        );
+    }
+
+    #[test]
+    fn read_buffer_chunked() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.hal.reads = vec![
+            (vec![Commands::ReadBuffer as u8, 0x84, 0], vec![1, 2, 3, 4]),
+            (vec![Commands::ReadBuffer as u8, 0x02, 0], vec![5, 6]),
+        ];
+
+        let mut buff = [0u8; 4];
+        radio.read_buffer_chunked(0x80, &mut buff, 4).unwrap();
+        assert_eq!(buff, [1, 2, 3, 4]);
+
+        // Offsets wrap at the end of the buffer
+        let mut buff = [0u8; 2];
+        radio.read_buffer_chunked(0xF0, &mut buff, 0x12).unwrap();
+        assert_eq!(buff, [5, 6]);
+
+        let mut buff = [0u8; 257];
+        assert_eq!(
+            radio.read_buffer_chunked(0, &mut buff, 0),
+            Err(Error::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn set_channel_band_edges() {
+        use radio::Channel as _;
+
+        let mut radio = Sx128x::build(MockHal::default());
+        let ch = |freq, bw| {
+            Channel::LoRa(LoRaChannel {
+                freq,
+                bw,
+                ..Default::default()
+            })
+        };
+
+        // 1600 kHz channel at the band edge extends out of band
+        assert_eq!(
+            radio.set_channel(&ch(2_400_000_000, LoRaBandwidth::Bw1600kHz)),
+            Err(Error::InvalidFrequency)
+        );
+        assert_eq!(
+            radio.set_channel(&ch(2_499_500_000, LoRaBandwidth::Bw1600kHz)),
+            Err(Error::InvalidFrequency)
+        );
+
+        radio
+            .set_channel(&ch(2_400_900_000, LoRaBandwidth::Bw1600kHz))
+            .unwrap();
+        radio
+            .set_channel(&ch(2_499_500_000, LoRaBandwidth::Bw800kHz))
+            .unwrap();
+    }
+
+    #[test]
+    fn set_frequency_xtal() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        radio.config.xtal_freq = 0;
+        assert_eq!(
+            radio.set_frequency(2_440_000_000),
+            Err(Error::InvalidConfiguration)
+        );
+        assert!(radio.hal.writes.is_empty());
+
+        let config = Config {
+            xtal_freq: 0,
+            ..Config::lora()
+        };
+        assert_eq!(config.validate(), Err(ConfigError::Frequency));
+
+        // Step count must fit the 24-bit frequency register
+        radio.config.xtal_freq = 13_000_000;
+        assert_eq!(
+            radio.set_frequency(2_440_000_000),
+            Err(Error::InvalidFrequency)
+        );
+
+        radio.config.xtal_freq = 52_000_000;
+        radio.set_frequency(2_440_000_000).unwrap();
+        let t = transactions::set_frequency(&radio.config, 2_440_000_000);
+        assert_eq!(
+            radio.hal.written(&[Commands::SetRfFrequency as u8]),
+            vec![t.data().to_vec()]
+        );
+    }
+
+    #[test]
+    fn wait_dio_high() {
+        let mut radio = Sx128x::build(MockHal::default());
+        assert_eq!(radio.wait_dio_high(10), Err(Error::Timeout));
+        assert_eq!(radio.wait_dio_high(0), Err(Error::Timeout));
+
+        radio.hal.dio = true;
+        assert_eq!(radio.wait_dio_high(0), Ok(()));
+    }
+
+    #[test]
+    fn clear_interrupts() {
+        let mut radio = Sx128x::build(MockHal::default());
+        let clear = [Commands::ClearIrqStatus as u8];
+
+        radio
+            .clear_interrupts(Irq::TX_DONE | Irq::RX_TX_TIMEOUT)
+            .unwrap();
+        assert_eq!(radio.hal.written(&clear), vec![vec![0x40, 0x01]]);
+
+        // Only selected pending flags are cleared
+        radio.hal.writes.clear();
+        radio.hal.reads = vec![(
+            vec![Commands::GetIrqStatus as u8, 0],
+            vec![0x00, (Irq::TX_DONE | Irq::RX_DONE).bits() as u8],
+        )];
+        assert_eq!(
+            radio.get_interrupts_masked(Irq::TX_DONE | Irq::CRC_ERROR),
+            Ok(Irq::TX_DONE | Irq::RX_DONE)
+        );
+        assert_eq!(radio.hal.written(&clear), vec![vec![0x00, 0x01]]);
+
+        radio.hal.writes.clear();
+        assert_eq!(
+            radio.get_interrupts_masked(Irq::CRC_ERROR),
+            Ok(Irq::TX_DONE | Irq::RX_DONE)
+        );
+        assert!(radio.hal.written(&clear).is_empty());
+    }
+
+    #[test]
+    fn poll_events() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        let mut poll = |irq: Irq| {
+            radio.hal.reads = vec![(
+                vec![Commands::GetIrqStatus as u8, 0],
+                vec![(irq.bits() >> 8) as u8, irq.bits() as u8],
+            )];
+            radio.poll().unwrap()
+        };
+
+        assert_eq!(poll(Irq::empty()), None);
+        assert_eq!(poll(Irq::TX_DONE), Some(RadioEvent::TxDone));
+        assert_eq!(
+            poll(Irq::RX_DONE | Irq::HEADER_VALID),
+            Some(RadioEvent::RxDone)
+        );
+        assert_eq!(
+            poll(Irq::RX_DONE | Irq::CRC_ERROR),
+            Some(RadioEvent::CrcError)
+        );
+        assert_eq!(poll(Irq::RX_TX_TIMEOUT), Some(RadioEvent::Timeout));
+        assert_eq!(
+            poll(Irq::CAD_DONE | Irq::CAD_ACTIVITY_DETECTED),
+            Some(RadioEvent::CadDone(true))
+        );
+        assert_eq!(
+            poll(Irq::PREAMBLE_DETECTED),
+            Some(RadioEvent::PreambleDetected)
+        );
+
+        // Interrupts are read and cleared once per poll
+        assert_eq!(
+            radio.hal.written(&[Commands::ClearIrqStatus as u8]).len(),
+            6
+        );
+    }
+
+    #[test]
+    fn poll_crc_error() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.packet_type = PacketType::LoRa;
+
+        let irq = (Irq::RX_DONE | Irq::CRC_ERROR).bits();
+        radio.hal.reads = vec![
+            (
+                vec![Commands::GetIrqStatus as u8, 0],
+                vec![(irq >> 8) as u8, irq as u8],
+            ),
+            (vec![Commands::GetRxBufferStatus as u8, 0], vec![2, 0]),
+        ];
+
+        // CRC errors reported by poll are reflected in the received packet info
+        assert_eq!(radio.poll(), Ok(Some(RadioEvent::CrcError)));
+
+        let mut buff = [0u8; 4];
+        let (_len, info) = radio.get_received(&mut buff).unwrap();
+        assert!(!info.crc_ok);
+    }
+
+    #[test]
+    fn receive_skips_modem_config() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.configure(&Config::gfsk()).unwrap();
+
+        let params =
+            |radio: &Sx128x<MockHal>| radio.hal.written(&[Commands::SetPacketParams as u8]).len();
+        let n = params(&radio);
+
+        // Applied configuration is not re-issued
+        radio.start_receive().unwrap();
+        radio.start_receive().unwrap();
+        assert_eq!(params(&radio), n);
+
+        // Transmission adjusts payload length, requiring reconfiguration
+        radio.start_transmit(&[1, 2, 3]).unwrap();
+        radio.start_receive().unwrap();
+        assert_eq!(params(&radio), n + 2);
+
+        // As does a channel change
+        let ch = radio.config.channel.clone();
+        radio.set_channel(&ch).unwrap();
+        radio.start_receive().unwrap();
+        assert_eq!(params(&radio), n + 3);
+    }
+
+    #[test]
+    fn packet_type_once() {
+        use radio::Transmit as _;
+
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.configure(&Config::flrc()).unwrap();
+        assert_eq!(
+            radio.hal.written(&[Commands::SetPacketType as u8]),
+            vec![vec![PacketType::Flrc as u8]]
+        );
+
+        // No packet type switching during a normal TX
+        radio.hal.writes.clear();
+        radio.start_transmit(&[1, 2, 3]).unwrap();
+        assert!(radio
+            .hal
+            .written(&[Commands::SetPacketType as u8])
+            .is_empty());
+    }
+
+    #[test]
+    fn calibrate_on_init() {
+        let new = |calibrate_on_init| {
+            let reg = Registers::LrFirmwareVersionMsb as u16;
+            let hal = MockHal {
+                reads: vec![(
+                    vec![Commands::ReadRegister as u8, (reg >> 8) as u8, reg as u8, 0],
+                    vec![0xA9, 0xB5],
+                )],
+                ..Default::default()
+            };
+            let config = Config {
+                calibrate_on_init,
+                ..Default::default()
+            };
+            let radio = Sx128x::new(hal, &config).unwrap();
+            radio.hal.written(&[Commands::Calibrate as u8])
+        };
+
+        assert_eq!(new(true), vec![vec![CalibrationParams::all().bits()]]);
+        assert!(new(false).is_empty());
     }
 }
@@ -0,0 +1,242 @@
+//! Mock HAL and bus implementations for driver unit tests
+
+use core::{cell::RefCell, convert::Infallible};
+use std::{rc::Rc, vec::Vec};
+
+use embedded_hal::{
+    delay::DelayNs,
+    digital::{self, InputPin, OutputPin, PinState},
+    spi::{self, SpiBus},
+};
+
+use crate::{base::Hal, device::*, Error};
+
+/// Mock HAL recording SPI transactions, reads return the configured
+/// response for a matching prefix or zeros, and register reads return
+/// the last value written. Responses repeated for a prefix are returned
+/// in order, with the last retained for subsequent reads
+#[derive(Default)]
+pub(crate) struct MockHal {
+    pub(crate) writes: Vec<(Vec<u8>, Vec<u8>)>,
+    pub(crate) reads: Vec<(Vec<u8>, Vec<u8>)>,
+    pub(crate) busy: bool,
+    pub(crate) dio: bool,
+    pub(crate) dio_edge: bool,
+}
+
+impl MockHal {
+    /// Fetch data written with the provided command prefix
+    pub(crate) fn written(&self, prefix: &[u8]) -> Vec<Vec<u8>> {
+        self.writes
+            .iter()
+            .filter(|(p, _)| p == prefix)
+            .map(|(_, d)| d.clone())
+            .collect()
+    }
+}
+
+impl Hal for MockHal {
+    type CommsError = ();
+    type PinError = ();
+
+    fn reset(&mut self) -> Result<(), Error<(), ()>> {
+        Ok(())
+    }
+
+    fn get_busy(&mut self) -> Result<PinState, Error<(), ()>> {
+        Ok(self.busy.into())
+    }
+
+    fn get_dio(&mut self) -> Result<PinState, Error<(), ()>> {
+        Ok(self.dio.into())
+    }
+
+    fn dio_asserted(&mut self) -> Result<bool, Error<(), ()>> {
+        Ok(core::mem::take(&mut self.dio_edge) || self.dio)
+    }
+
+    fn delay_ms(&mut self, _ms: u32) {}
+
+    fn delay_us(&mut self, _us: u32) {}
+
+    fn delay_ns(&mut self, _ns: u32) {}
+
+    fn write_cmd(&mut self, command: u8, data: &[u8]) -> Result<(), Error<(), ()>> {
+        self.prefix_write(&[command], data)
+    }
+
+    fn read_cmd(&mut self, command: u8, data: &mut [u8]) -> Result<(), Error<(), ()>> {
+        self.prefix_read(&[command, 0], data)
+    }
+
+    fn write_regs(&mut self, reg: u16, data: &[u8]) -> Result<(), Error<(), ()>> {
+        let prefix = [Commands::WiteRegister as u8, (reg >> 8) as u8, reg as u8];
+        self.prefix_write(&prefix, data)
+    }
+
+    fn read_regs(&mut self, reg: u16, data: &mut [u8]) -> Result<(), Error<(), ()>> {
+        let prefix = [Commands::ReadRegister as u8, (reg >> 8) as u8, reg as u8, 0];
+        self.prefix_read(&prefix, data)?;
+
+        // Scripted reads model the device, otherwise registers read back the
+        // last value written
+        if self.reads.iter().any(|(p, _)| p == &prefix) {
+            return Ok(());
+        }
+        let written = [Commands::WiteRegister as u8, (reg >> 8) as u8, reg as u8];
+        if let Some(d) = self.written(&written).last() {
+            let n = d.len().min(data.len());
+            data[..n].copy_from_slice(&d[..n]);
+        }
+        Ok(())
+    }
+
+    fn write_buff(&mut self, offset: u8, data: &[u8]) -> Result<(), Error<(), ()>> {
+        self.prefix_write(&[Commands::WriteBuffer as u8, offset], data)
+    }
+
+    fn read_buff(&mut self, offset: u8, data: &mut [u8]) -> Result<(), Error<(), ()>> {
+        self.prefix_read(&[Commands::ReadBuffer as u8, offset, 0], data)
+    }
+
+    fn prefix_read(&mut self, prefix: &[u8], data: &mut [u8]) -> Result<(), Error<(), ()>> {
+        data.iter_mut().for_each(|d| *d = 0);
+        let mut matches = self
+            .reads
+            .iter()
+            .enumerate()
+            .filter(|(_, (p, _))| p == prefix);
+        if let Some((i, (_, r))) = matches.next() {
+            data[..r.len()].copy_from_slice(r);
+            if matches.next().is_some() {
+                self.reads.remove(i);
+            }
+        }
+        Ok(())
+    }
+
+    fn prefix_write(&mut self, prefix: &[u8], data: &[u8]) -> Result<(), Error<(), ()>> {
+        self.writes.push((prefix.to_vec(), data.to_vec()));
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl crate::asynch::AsyncHal for MockHal {
+    type CommsError = ();
+    type PinError = ();
+
+    async fn reset(&mut self) -> Result<(), Error<(), ()>> {
+        Ok(())
+    }
+
+    async fn wait_busy(&mut self) -> Result<(), Error<(), ()>> {
+        Ok(())
+    }
+
+    async fn wait_dio(&mut self) -> Result<(), Error<(), ()>> {
+        Ok(())
+    }
+
+    async fn prefix_write(&mut self, prefix: &[u8], data: &[u8]) -> Result<(), Error<(), ()>> {
+        Hal::prefix_write(self, prefix, data)
+    }
+
+    async fn prefix_read(&mut self, prefix: &[u8], data: &mut [u8]) -> Result<(), Error<(), ()>> {
+        Hal::prefix_read(self, prefix, data)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum BusEvent {
+    Select(u8),
+    Deselect(u8),
+    Write(Vec<u8>),
+}
+
+pub(crate) type BusLog = Rc<RefCell<Vec<BusEvent>>>;
+
+/// Mock SPI bus recording writes
+pub(crate) struct MockBus(pub(crate) BusLog);
+
+impl spi::ErrorType for MockBus {
+    type Error = Infallible;
+}
+
+impl SpiBus for MockBus {
+    fn read(&mut self, words: &mut [u8]) -> Result<(), Infallible> {
+        words.iter_mut().for_each(|w| *w = 0);
+        Ok(())
+    }
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+        self.0.borrow_mut().push(BusEvent::Write(words.to_vec()));
+        Ok(())
+    }
+
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Infallible> {
+        self.write(write)?;
+        self.read(read)
+    }
+
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Infallible> {
+        self.write(words)?;
+        self.read(words)
+    }
+
+    fn flush(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+/// Mock chip select recording select / deselect on the bus
+pub(crate) struct MockCs(pub(crate) u8, pub(crate) BusLog);
+
+impl digital::ErrorType for MockCs {
+    type Error = Infallible;
+}
+
+impl OutputPin for MockCs {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.1.borrow_mut().push(BusEvent::Select(self.0));
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.1.borrow_mut().push(BusEvent::Deselect(self.0));
+        Ok(())
+    }
+}
+
+/// Mock busy / ready / SDN pin, always low
+pub(crate) struct MockPin;
+
+impl digital::ErrorType for MockPin {
+    type Error = Infallible;
+}
+
+impl InputPin for MockPin {
+    fn is_high(&mut self) -> Result<bool, Infallible> {
+        Ok(false)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Infallible> {
+        Ok(true)
+    }
+}
+
+impl OutputPin for MockPin {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+pub(crate) struct MockDelay;
+
+impl DelayNs for MockDelay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
@@ -2,8 +2,13 @@
 
 pub use crate::{Error as Sx128xError, Sx128x, Sx128xSpi};
 
-pub use crate::device::{Channel, Config, Modem, PacketInfo, RegulatorMode, State};
+pub use crate::device::{Channel, Config, ConfigBuilder, Modem, PacketInfo, RegulatorMode, State};
 
+#[cfg(feature = "modem-ble")]
+pub use crate::device::ble::{BleChannel, BleConfig};
+#[cfg(feature = "modem-flrc")]
 pub use crate::device::flrc::{FlrcChannel, FlrcConfig};
+#[cfg(feature = "modem-gfsk")]
 pub use crate::device::gfsk::{GfskChannel, GfskConfig};
+#[cfg(feature = "modem-lora")]
 pub use crate::device::lora::{LoRaChannel, LoRaConfig};
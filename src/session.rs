@@ -0,0 +1,216 @@
+//! High-throughput transmit and receive sessions
+//!
+//! `Sx128x::start_transmit` and `Sx128x::start_receive` reconfigure the modem,
+//! buffer and IRQs for every packet, which is robust but limits throughput for
+//! high bitrate (FLRC / GFSK) links. Sessions apply this configuration once on
+//! creation and assume it remains fixed, so the per-packet methods issue only
+//! the minimum SPI transactions required.
+//!
+//! Per packet, `start_transmit` re-issues the modem, buffer, IRQ (and for ranging,
+//! role) configuration whereas `TxSession::send` issues 2 transactions (the buffer
+//! write and `SetTx`), plus a `SetPacketParams` write where the payload length
+//! changes. For a 127 byte FLRC packet this is 7 transactions / 159 SPI bytes
+//! per packet via `start_transmit` against 2 transactions / 133 bytes via
+//! `TxSession::send` (6 / 155 for GFSK and LoRa), each transaction also
+//! awaiting BUSY. Over-the-air throughput has not been benchmarked, as this
+//! depends on the SPI clock, BUSY latency and platform. On the receive side
+//! `RxSession::recv` re-enters RX with a single `SetRx` rather than the full
+//! `start_receive` sequence (or none in `Timeout::Continuous` mode).
+//!
+//! The radio must not be reconfigured while a session is active (enforced by the
+//! session borrowing the radio), and no validation of state or configuration is
+//! performed by the per-packet methods.
+
+use core::convert::TryFrom;
+
+use radio::{Interrupts as _, State as _};
+
+use crate::{base, device::*, Error, Sx128x};
+
+/// High-throughput transmit session, see the [module](self) documentation
+pub struct TxSession<'a, Hal> {
+    pub(crate) radio: &'a mut Sx128x<Hal>,
+    modem: Modem,
+    payload_len: Option<u8>,
+}
+
+impl<'a, Hal> TxSession<'a, Hal>
+where
+    Hal: base::Hal,
+{
+    pub(crate) fn new(radio: &'a mut Sx128x<Hal>) -> Result<Self, <Hal as base::HalError>::E> {
+        radio.set_state(State::StandbyRc)?;
+
+        let modem = radio.config.modem.clone();
+        radio.configure_modem(&modem)?;
+        radio.set_buff_base_addr(radio.config.tx_base_addr, radio.config.rx_base_addr)?;
+
+        // Ranging sessions transmit as the initiator, as with `start_transmit`
+        if radio.is_ranging() {
+            radio.write_cmd(
+                Commands::SetRangingRole as u8,
+                &[RangingRole::Initiator as u8],
+            )?;
+        }
+
+        let irqs = Irq::TX_DONE | Irq::RX_TX_TIMEOUT;
+        radio.set_irq_dio_mask(irqs, irqs, DioMask::empty(), DioMask::empty())?;
+
+        Ok(Self {
+            radio,
+            modem,
+            payload_len: None,
+        })
+    }
+
+    /// Start transmitting a packet, completion is polled with `check_transmit`
+    pub fn send(&mut self, data: &[u8]) -> Result<(), <Hal as base::HalError>::E> {
+        if data.len() > self.modem.max_payload_len() {
+            return Err(Error::InvalidLength);
+        }
+        let len = u8::try_from(data.len()).map_err(|_| Error::InvalidLength)?;
+
+        // Packet parameters only need updating on a change in length
+        if self.payload_len != Some(len) {
+            self.modem.set_payload_len(len);
+            let t = transactions::set_packet_params(&self.modem);
+            self.radio.write_transaction(&t)?;
+            self.payload_len = Some(len);
+        }
+
+        self.radio
+            .hal
+            .write_buff(self.radio.config.tx_base_addr, data)?;
+
+        let timeout = self.radio.config.rf_timeout.clone();
+        self.radio.set_tx(&timeout)
+    }
+
+    /// Check for transmit completion, returning `Error::Timeout` on a TX timeout
+    pub fn check_transmit(&mut self) -> Result<bool, <Hal as base::HalError>::E> {
+        let irq = self.radio.get_interrupts(true)?;
+
+        if irq.contains(Irq::RX_TX_TIMEOUT) {
+            return Err(Error::Timeout);
+        }
+
+        Ok(irq.contains(Irq::TX_DONE))
+    }
+}
+
+/// High-throughput receive session, see the [module](self) documentation
+pub struct RxSession<'a, Hal> {
+    pub(crate) radio: &'a mut Sx128x<Hal>,
+    continuous: bool,
+}
+
+impl<'a, Hal> RxSession<'a, Hal>
+where
+    Hal: base::Hal,
+{
+    pub(crate) fn new(radio: &'a mut Sx128x<Hal>) -> Result<Self, <Hal as base::HalError>::E> {
+        let timeout = radio.config.rf_timeout.clone();
+        radio.start_receive_timeout(&timeout)?;
+
+        Ok(Self {
+            radio,
+            continuous: timeout == Timeout::Continuous,
+        })
+    }
+
+    /// Poll for a received packet, returning the received length and packet
+    /// information if available.
+    ///
+    /// Receive errors are returned after the radio is re-armed, so `recv` may be
+    /// called again without further action.
+    pub fn recv(
+        &mut self,
+        data: &mut [u8],
+    ) -> Result<Option<(usize, PacketInfo)>, <Hal as base::HalError>::E> {
+        let irq = self.radio.get_interrupts(true)?;
+
+        let res = if irq.contains(Irq::CRC_ERROR) {
+            Err(Error::InvalidCrc)
+        } else if irq.contains(Irq::RX_TX_TIMEOUT) {
+            Err(Error::Timeout)
+        } else if irq.contains(Irq::SYNCWORD_ERROR) {
+            Err(Error::InvalidSync)
+        } else if irq.contains(Irq::RX_DONE) {
+            self.read(data).map(Some)
+        } else {
+            return Ok(None);
+        };
+
+        // Re-enter RX unless the radio remains in continuous mode
+        if !self.continuous {
+            let timeout = self.radio.config.rf_timeout.clone();
+            self.radio.set_rx(&timeout)?;
+        }
+
+        res
+    }
+
+    fn read(&mut self, data: &mut [u8]) -> Result<(usize, PacketInfo), <Hal as base::HalError>::E> {
+        let RxBufferStatus { ptr, len } = self.radio.get_rx_buffer_status()?;
+        let len = len as usize;
+
+        if data.len() < len {
+            return Err(Error::InvalidLength);
+        }
+
+        self.radio.hal.read_buff(ptr, &mut data[..len])?;
+        self.radio.rx_pending = false;
+
+        let mut info = PacketInfo::default();
+        self.radio.get_packet_info(&mut info)?;
+
+        Ok((len, info))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use radio::Transmit;
+
+    use crate::mock::MockHal;
+    use crate::{
+        device::{lora::*, *},
+        Sx128x,
+    };
+
+    #[test]
+    fn tx_session() {
+        let mut radio = Sx128x::build(MockHal::default());
+
+        radio.start_transmit(&[0xAA; 16]).unwrap();
+        let start_transmit = radio.hal.writes.len();
+
+        let mut session = radio.tx_session().unwrap();
+        session.send(&[0xAA; 16]).unwrap();
+
+        // Subsequent same-length packets issue only the buffer write and SetTx
+        session.radio.hal.writes.clear();
+        session.send(&[0x55; 16]).unwrap();
+        assert_eq!(
+            session.radio.hal.writes,
+            vec![
+                (vec![Commands::WriteBuffer as u8, 0], vec![0x55; 16]),
+                (vec![Commands::SetTx as u8], vec![0, 0, 0]),
+            ]
+        );
+        assert!(session.radio.hal.writes.len() < start_transmit);
+    }
+
+    #[test]
+    fn tx_session_ranging() {
+        let mut radio = Sx128x::build(MockHal::default());
+        radio.config.modem = Modem::Ranging(LoRaConfig::default());
+        radio.config.channel = Channel::Ranging(LoRaChannel::default());
+
+        let session = radio.tx_session().unwrap();
+        assert_eq!(
+            session.radio.hal.written(&[Commands::SetRangingRole as u8]),
+            vec![vec![RangingRole::Initiator as u8]]
+        );
+    }
+}
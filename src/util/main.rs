@@ -28,6 +28,19 @@ fn main() {
         .without_time()
         .try_init();
 
+    // Validate configuration prior to connecting
+    let rf_config = opts.rf_config();
+    if let Err(e) = rf_config.validate() {
+        error!("Invalid radio configuration: {:?}", e);
+        return;
+    }
+    if let Some(syncword) = &opts.syncword {
+        if let Err(e) = rf_config.validate_sync_word(&syncword.0) {
+            error!("Invalid syncword: {:?}", e);
+            return;
+        }
+    }
+
     debug!("Connecting to platform SPI");
     trace!("with config: {:?}", opts.spi_config);
 
@@ -39,8 +52,6 @@ fn main() {
         }
     };
 
-    let rf_config = opts.rf_config();
-
     debug!("Config: {:?}", rf_config);
 
     info!("Initialising Radio");
@@ -65,6 +76,12 @@ fn main() {
             info!("Silicon version: 0x{:X}", version);
         }
         _ => {
+            if let Command::Ble(c) = &opts.command {
+                if let Err(e) = radio.set_ble_access_address(c.access_address) {
+                    error!("Error setting access address: {:?}", e);
+                }
+                debug!("Access address: 0x{:08x}", c.access_address);
+            }
             if let Some(mut syncword) = opts.syncword {
                 if let Err(e) = radio.set_syncword(1, &mut syncword.0) {
                     error!("Error setting syncword: {:?}", e);
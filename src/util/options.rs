@@ -8,10 +8,12 @@ use radio::helpers::Operation;
 
 use radio_sx128x::{
     device::{
+        ble,
         common::{self, GfskFlrcCrcModes::*, PreambleLength::*},
-        flrc, lora,
+        flrc, lora, TX_POWER_MAX, TX_POWER_MIN,
     },
     prelude::*,
+    FREQ_MAX, FREQ_MIN,
 };
 
 #[derive(Parser)]
@@ -37,6 +39,10 @@ pub struct Options {
     #[clap(long, default_value = "16", env = "PREAMBLE_LEN")]
     pub preamble_len: u8,
 
+    /// Set TX power in dBm (-18 to 13 dBm)
+    #[clap(long = "power-dbm", default_value = "10", env = "POWER_DBM", value_parser = clap::value_parser!(i8).range(i64::from(TX_POWER_MIN)..=i64::from(TX_POWER_MAX)))]
+    pub power: i8,
+
     #[clap(long, default_value = "info")]
     /// Configure radio log level
     pub log_level: LevelFilter,
@@ -63,6 +69,10 @@ pub enum Command {
     #[clap(name = "flrc")]
     /// FLRC mode configuration and operations
     Flrc(FlrcCommand),
+
+    #[clap(name = "ble")]
+    /// BLE mode configuration and operations
+    Ble(BleCommand),
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -76,6 +86,27 @@ impl std::str::FromStr for HexData {
     }
 }
 
+/// Parse and validate an operating frequency in GHz
+fn parse_freq_ghz(s: &str) -> Result<f32, String> {
+    let f = f32::from_str(s).map_err(|e| e.to_string())?;
+
+    let (min, max) = (FREQ_MIN as f32 / 1e9, FREQ_MAX as f32 / 1e9);
+    if !(min..=max).contains(&f) {
+        return Err(format!(
+            "Frequency must be in a range of {} to {} GHz",
+            min, max
+        ));
+    }
+
+    Ok(f)
+}
+
+/// Parse a 32-bit value in hex (base 16), with an optional `0x` prefix
+fn parse_hex_u32(s: &str) -> Result<u32, String> {
+    let s = s.trim_start_matches("0x");
+    u32::from_str_radix(s, 16).map_err(|e| e.to_string())
+}
+
 impl Command {
     pub fn operation(&self) -> Option<Operation> {
         match self {
@@ -83,6 +114,7 @@ impl Command {
             Command::LoRa(c) => Some(c.operation.clone()),
             Command::Gfsk(c) => Some(c.operation.clone()),
             Command::Flrc(c) => Some(c.operation.clone()),
+            Command::Ble(c) => Some(c.operation.clone()),
         }
     }
 }
@@ -96,6 +128,8 @@ impl Options {
             false => RegulatorMode::Ldo,
         };
 
+        config.pa_config.power = self.power;
+
         // Generate configurations
         match &self.command {
             Command::LoRa(lora_config) => {
@@ -111,6 +145,8 @@ impl Options {
 
                 let mut channel = LoRaChannel::default();
                 channel.freq = (lora_config.frequency * 1e9) as u32;
+                channel.sf = lora_config.spreading_factor;
+                channel.bw = lora_config.bandwidth;
 
                 config.channel = Channel::LoRa(channel);
             }
@@ -170,6 +206,22 @@ impl Options {
 
                 config.channel = Channel::Gfsk(channel);
             }
+            Command::Ble(ble_config) => {
+                // Set to BLE mode, only 3 byte CRCs are supported
+                let mut modem = BleConfig::default();
+
+                modem.crc_field = match self.crc_mode {
+                    0 => ble::BleCrcFields::BLE_CRC_OFF,
+                    _ => ble::BleCrcFields::BLE_CRC_3B,
+                };
+
+                config.modem = Modem::Ble(modem);
+
+                let mut channel = BleChannel::default();
+                channel.freq = (ble_config.frequency * 1e9) as u32;
+
+                config.channel = Channel::Ble(channel);
+            }
             _ => (),
         }
 
@@ -182,9 +234,19 @@ impl Options {
 pub struct LoRaCommand {
     /// Operating frequency in GHz
     /// This must be in a range of 2.40 to 2.50 GHz
-    #[clap(long = "freq-ghz", default_value = "2.44")]
+    #[clap(long = "freq-ghz", default_value = "2.44", env = "LORA_FREQ_GHZ", value_parser = parse_freq_ghz)]
     pub frequency: f32,
 
+    /// LoRa spreading factor
+    /// (options: 5, 6, 7, 8, 9, 10, 11, 12)
+    #[clap(long = "sf", default_value = "8", env = "LORA_SF")]
+    pub spreading_factor: lora::LoRaSpreadingFactor,
+
+    /// LoRa bandwidth in kHz
+    /// (options: 200, 400, 800, 1600)
+    #[clap(long = "bw-khz", default_value = "200", env = "LORA_BW_KHZ")]
+    pub bandwidth: lora::LoRaBandwidth,
+
     #[clap(subcommand)]
     /// Operation to execute
     pub operation: Operation,
@@ -195,7 +257,7 @@ pub struct LoRaCommand {
 pub struct GfskCommand {
     /// Operating frequency in GHz
     /// This must be in a range of 2.40 to 2.50 GHz
-    #[clap(long = "freq-ghz", default_value = "2.44")]
+    #[clap(long = "freq-ghz", default_value = "2.44", env = "GFSK_FREQ_GHZ", value_parser = parse_freq_ghz)]
     pub frequency: f32,
 
     #[clap(subcommand)]
@@ -208,7 +270,7 @@ pub struct GfskCommand {
 pub struct FlrcCommand {
     /// Operating frequency in GHz
     /// This must be in a range of 2.40 to 2.50 GHz
-    #[clap(long = "freq-ghz", default_value = "2.44", env = "FLRC_FREQ_GHZ")]
+    #[clap(long = "freq-ghz", default_value = "2.44", env = "FLRC_FREQ_GHZ", value_parser = parse_freq_ghz)]
     pub frequency: f32,
 
     /// FLRC bitrate-bandwidth in kbps
@@ -229,3 +291,20 @@ pub struct FlrcCommand {
     /// Operation to execute
     pub operation: Operation,
 }
+
+/// BLE mode command wrapper
+#[derive(Parser, PartialEq, Debug)]
+pub struct BleCommand {
+    /// Operating frequency in GHz, defaults to advertising channel 37
+    /// This must be in a range of 2.40 to 2.50 GHz
+    #[clap(long = "freq-ghz", default_value = "2.402", env = "BLE_FREQ_GHZ", value_parser = parse_freq_ghz)]
+    pub frequency: f32,
+
+    /// Access address in hex (base 16), defaults to the advertising access address
+    #[clap(long, default_value = "8E89BED6", env = "BLE_ACCESS_ADDRESS", value_parser = parse_hex_u32)]
+    pub access_address: u32,
+
+    #[clap(subcommand)]
+    /// Operation to execute
+    pub operation: Operation,
+}